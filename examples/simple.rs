@@ -6,7 +6,7 @@ fn main() {
     println!(
         "{}",
         Mrkdwn::from(read_to_string("examples/sample.md").unwrap().as_str())
-            .mrkdwnify()
+            .mrkdwnify_raw()
             .unwrap()
     );
 }
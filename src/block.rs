@@ -2,7 +2,10 @@ use std::fmt::{Display, Formatter, Result};
 
 use serde_json::{Value, json};
 
-use crate::Block::{Divider, Header, Image, Section, Table};
+use crate::{
+    Block::{Context, Divider, Header, Image, Markdown, RichText, Section, Table},
+    Error, Mrkdwn,
+};
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Block {
@@ -21,13 +24,34 @@ pub enum Block {
     },
     /// A [table block](https://docs.slack.dev/reference/block-kit/blocks/table-block/).
     ///
-    /// - `column_settings`: per-column alignment (`left`/`center`/`right`). `None` keeps the
-    ///   default (left) alignment for that column.
+    /// - `column_settings`: the layout of each column, from the first one on. Missing columns
+    ///   keep the default layout.
     /// - `rows`: each row is a list of pre-built cell values (`raw_text` or `rich_text`).
     Table {
-        column_settings: Vec<Option<String>>,
+        column_settings: Vec<ColumnSettings>,
         rows: Vec<Vec<Value>>,
     },
+    /// A [rich text block](https://docs.slack.dev/reference/block-kit/blocks/rich-text-block/).
+    ///
+    /// Each element is a pre-built `rich_text_section`, `rich_text_list`, `rich_text_quote`, or
+    /// `rich_text_preformatted` value.
+    RichText(Vec<Value>),
+    /// A [markdown block](https://docs.slack.dev/reference/block-kit/blocks/markdown-block/)
+    /// holding standard Markdown, which Slack renders itself.
+    Markdown(String),
+    /// A [context block](https://docs.slack.dev/reference/block-kit/blocks/context-block/) of
+    /// mrkdwn texts, shown in small print side by side.
+    Context(Vec<String>),
+}
+
+/// The layout of a table column, one of the `column_settings` of a table block.
+///
+/// - `align`: `left`, `center`, or `right`. `None` keeps the default (left) alignment.
+/// - `is_wrapped`: whether long text wraps onto more lines instead of being cut off.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ColumnSettings {
+    pub align: Option<String>,
+    pub is_wrapped: bool,
 }
 
 impl From<Block> for Value {
@@ -68,15 +92,25 @@ impl From<Block> for Value {
                     "rows": rows,
                 });
 
-                // Trim trailing columns that use the default alignment: Slack applies
+                // Trim trailing columns that use the default layout: Slack applies
                 // `column_settings` to the leading columns only, so they can be omitted.
-                let last = column_settings.iter().rposition(Option::is_some);
+                let default = ColumnSettings::default();
+                let last = column_settings.iter().rposition(|settings| *settings != default);
                 if let Some(last) = last {
                     let settings: Vec<Value> = column_settings[..=last]
                         .iter()
-                        .map(|align| match align {
-                            Some(align) => json!({ "align": align }),
-                            None => Value::Null,
+                        .map(|settings| {
+                            if *settings == default {
+                                return Value::Null;
+                            }
+                            let mut value = json!({});
+                            if let Some(align) = &settings.align {
+                                value["align"] = json!(align);
+                            }
+                            if settings.is_wrapped {
+                                value["is_wrapped"] = json!(true);
+                            }
+                            value
                         })
                         .collect();
                     table["column_settings"] = Value::Array(settings);
@@ -84,6 +118,114 @@ impl From<Block> for Value {
 
                 table
             }
+            RichText(elements) => json!({
+                "type": "rich_text",
+                "elements": elements,
+            }),
+            Markdown(text) => json!({
+                "type": "markdown",
+                "text": text,
+            }),
+            Context(texts) => json!({
+                "type": "context",
+                "elements": texts
+                    .into_iter()
+                    .map(|text| json!({ "type": "mrkdwn", "text": text }))
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<Value> for Block {
+    type Error = Error;
+
+    /// Reads a Block Kit block, the inverse of `From<Block> for Value`.
+    ///
+    /// A `section` with `plain_text` is escaped into mrkdwn, and its `fields` are appended to
+    /// its text one per line. The images of a `context` are dropped, keeping its texts. Block
+    /// types this crate does not model, such as `actions`, return [`Error::UnsupportedBlock`].
+    fn try_from(value: Value) -> std::result::Result<Self, Self::Error> {
+        let invalid = |message: &str| Error::InvalidBlock { message: message.to_string() };
+        let string = |value: &Value, what: &str| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| invalid(&format!("missing {what}")))
+        };
+        // A text object, converted to mrkdwn when it is `plain_text`.
+        let mrkdwn = |text: &Value| -> std::result::Result<String, Error> {
+            let content = string(&text["text"], "text")?;
+            Ok(match text["type"].as_str() {
+                Some("plain_text") => Mrkdwn::escape(&content),
+                _ => content,
+            })
+        };
+
+        match value["type"].as_str() {
+            Some("header") => Ok(Header(string(&value["text"]["text"], "header text")?)),
+            Some("divider") => Ok(Divider),
+            Some("markdown") => Ok(Markdown(string(&value["text"], "markdown text")?)),
+            Some("section") => {
+                let text = match &value["text"] {
+                    Value::Null => None,
+                    text => Some(mrkdwn(text)?),
+                };
+                let fields = match value["fields"].as_array() {
+                    Some(fields) => {
+                        fields.iter().map(mrkdwn).collect::<std::result::Result<_, _>>()?
+                    }
+                    None => Vec::new(),
+                };
+                let lines: Vec<String> = text.into_iter().chain(fields).collect();
+                if lines.is_empty() {
+                    return Err(invalid("missing section text"));
+                }
+                Ok(Section(lines.join("\n")))
+            }
+            Some("image") => Ok(Image {
+                url: string(&value["image_url"], "image_url")?,
+                alt_text: string(&value["alt_text"], "alt_text")?,
+                title: value["title"]["text"].as_str().map(str::to_string),
+            }),
+            Some("table") => {
+                let rows = value["rows"]
+                    .as_array()
+                    .ok_or_else(|| invalid("missing table rows"))?
+                    .iter()
+                    .map(|row| row.as_array().cloned().ok_or_else(|| invalid("invalid table row")))
+                    .collect::<std::result::Result<_, _>>()?;
+                let column_settings = value["column_settings"]
+                    .as_array()
+                    .map(|settings| {
+                        settings
+                            .iter()
+                            .map(|setting| ColumnSettings {
+                                align: setting["align"].as_str().map(str::to_string),
+                                is_wrapped: setting["is_wrapped"].as_bool().unwrap_or_default(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Ok(Table { column_settings, rows })
+            }
+            Some("context") => Ok(Context(
+                value["elements"]
+                    .as_array()
+                    .ok_or_else(|| invalid("missing context elements"))?
+                    .iter()
+                    .filter(|element| element["type"] != "image")
+                    .map(mrkdwn)
+                    .collect::<std::result::Result<_, _>>()?,
+            )),
+            Some("rich_text") => Ok(RichText(
+                value["elements"]
+                    .as_array()
+                    .cloned()
+                    .ok_or_else(|| invalid("missing rich_text elements"))?,
+            )),
+            Some(block_type) => Err(Error::UnsupportedBlock { block_type: block_type.to_string() }),
+            None => Err(invalid("missing block type")),
         }
     }
 }
@@ -96,6 +238,9 @@ impl Display for Block {
             Section(text) => write!(f, "Section: {text}"),
             Image { url, .. } => write!(f, "Image: {url}"),
             Table { rows, .. } => write!(f, "Table: {} rows", rows.len()),
+            RichText(elements) => write!(f, "RichText: {} elements", elements.len()),
+            Markdown(text) => write!(f, "Markdown: {text}"),
+            Context(texts) => write!(f, "Context: {}", texts.join(" ")),
         }
     }
 }
@@ -0,0 +1,216 @@
+//! Dates written as HTML `<time datetime="…">` tags, which Slack shows in each reader's own time
+//! zone.
+//!
+//! The `datetime` attribute is parsed offline as an RFC 3339 timestamp such as
+//! `2026-10-17T12:00Z` or `2026-10-17T14:00:00+02:00`, or as a bare date such as `2026-10-17`.
+
+use markdown::mdast::Node;
+use serde_json::{Value, json};
+
+use crate::Mrkdwn;
+
+/// A point in time from a `<time>` tag.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct Date {
+    /// Seconds since the Unix epoch.
+    timestamp: i64,
+    /// Whether the tag gave a time of day, or only a date.
+    has_time: bool,
+}
+
+impl Date {
+    /// Parses an opening (or self-closing) `<time datetime="…">` tag. Tags without a valid
+    /// `datetime` attribute return `None`.
+    pub(crate) fn from_html(html: &str) -> Option<Self> {
+        let (timestamp, has_time) = parse_datetime(datetime_attribute(html)?)?;
+        Some(Self { timestamp, has_time })
+    }
+
+    /// Returns Slack's format string for the date, e.g. `{date_short} {time}`.
+    fn format(self) -> &'static str {
+        if self.has_time { "{date_short} {time}" } else { "{date_short}" }
+    }
+
+    /// Returns the text shown by clients that cannot format the date, e.g. `2026-10-17 12:00 UTC`.
+    pub(crate) fn fallback(self) -> String {
+        let (year, month, day) = civil_from_days(self.timestamp.div_euclid(86400));
+        let seconds = self.timestamp.rem_euclid(86400);
+        if self.has_time {
+            format!(
+                "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+                seconds / 3600,
+                seconds % 3600 / 60
+            )
+        } else {
+            format!("{year:04}-{month:02}-{day:02}")
+        }
+    }
+
+    /// Formats the date as a mrkdwn `<!date^…>` token.
+    pub(crate) fn to_mrkdwn(self) -> String {
+        format!("<!date^{}^{}|{}>", self.timestamp, self.format(), Mrkdwn::escape(&self.fallback()))
+    }
+
+    /// Builds the `rich_text` section element for the date.
+    pub(crate) fn to_element(self) -> Value {
+        json!({
+            "type": "date",
+            "timestamp": self.timestamp,
+            "format": self.format(),
+            "fallback": self.fallback(),
+        })
+    }
+}
+
+/// Drops the content of `<time>` tags whose `datetime` is valid, along with their closing tags,
+/// so that the opening tag alone stands for the date. Applies to every node under `nodes`.
+pub(crate) fn collapse(nodes: &mut Vec<Node>) {
+    let mut i = 0;
+    while i < nodes.len() {
+        if let Node::Html(html) = &nodes[i]
+            && Date::from_html(&html.value).is_some()
+            && !html.value.trim_end().ends_with("/>")
+            && let Some(close) = nodes[i + 1..].iter().position(|node| {
+                matches!(node, Node::Html(html) if html.value.trim().eq_ignore_ascii_case("</time>"))
+            })
+        {
+            nodes.drain(i + 1..=i + 1 + close);
+        }
+        if let Some(children) = nodes[i].children_mut() {
+            collapse(children);
+        }
+        i += 1;
+    }
+}
+
+/// Returns the `datetime` attribute of a `<time>` tag.
+fn datetime_attribute(html: &str) -> Option<&str> {
+    let rest = html.strip_prefix('<')?;
+    let end = rest.find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')?;
+    let (name, mut rest) = rest.split_at(end);
+    if !name.eq_ignore_ascii_case("time") {
+        return None;
+    }
+
+    loop {
+        rest = rest.trim_start();
+        let end = rest.find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '>' | '/'))?;
+        let (attribute, after) = rest.split_at(end);
+        if attribute.is_empty() {
+            return None;
+        }
+        let after = after.trim_start();
+        let (value, after) = match after.strip_prefix('=').map(str::trim_start) {
+            Some(value) => match value.chars().next()? {
+                quote @ ('"' | '\'') => {
+                    let close = value[1..].find(quote)? + 1;
+                    (&value[1..close], &value[close + 1..])
+                }
+                _ => value.split_at(value.find(|c: char| c.is_ascii_whitespace() || c == '>')?),
+            },
+            None => ("", after),
+        };
+        if attribute.eq_ignore_ascii_case("datetime") {
+            return Some(value.trim());
+        }
+        rest = after;
+    }
+}
+
+/// Parses an RFC 3339 timestamp into seconds since the Unix epoch, and whether it has a time of
+/// day. A bare date stands for noon UTC, which is the same date in nearly every time zone.
+fn parse_datetime(text: &str) -> Option<(i64, bool)> {
+    let (date, time) = match text.find(['T', 't', ' ']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+
+    let [year, month, day] = fields(date, '-', [4, 2, 2])?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+
+    let Some(time) = time else {
+        return Some((days * 86400 + 12 * 3600, false));
+    };
+    let zone = time.find(['Z', 'z', '+', '-'])?;
+    let (clock, zone) = time.split_at(zone);
+    let clock = clock.split_once('.').map_or(clock, |(clock, fraction)| {
+        if fraction.bytes().all(|b| b.is_ascii_digit()) { clock } else { "" }
+    });
+    let (hour, minute, second) = match clock.len() {
+        5 => fields(clock, ':', [2, 2]).map(|[hour, minute]| (hour, minute, 0))?,
+        8 => fields(clock, ':', [2, 2, 2]).map(|[hour, minute, second]| (hour, minute, second))?,
+        _ => return None,
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let offset = match zone {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = if zone.starts_with('-') { -1 } else { 1 };
+            let zone = &zone[1..];
+            let [hours, minutes] = match zone.len() {
+                4 => fields(&format!("{}:{}", zone.get(..2)?, zone.get(2..)?), ':', [2, 2])?,
+                _ => fields(zone, ':', [2, 2])?,
+            };
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    Some((days * 86400 + hour * 3600 + minute * 60 + second - offset, true))
+}
+
+/// Splits `text` at `separator` into numbers of exactly the given digit counts.
+fn fields<const N: usize>(text: &str, separator: char, digits: [usize; N]) -> Option<[i64; N]> {
+    let mut parts = text.split(separator);
+    let mut numbers = [0; N];
+    for (number, digits) in numbers.iter_mut().zip(digits) {
+        let part = parts.next()?;
+        if part.len() != digits || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *number = part.parse().ok()?;
+    }
+    parts.next().is_none().then_some(numbers)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Returns the `(year, month, day)` of the date `days` after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
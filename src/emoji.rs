@@ -0,0 +1,151 @@
+//! Emoji in text: `:shortcode:`s and Unicode emoji.
+//!
+//! GitHub names its emoji after [gemoji], while Slack uses its own names for some of them, so
+//! shortcodes are translated to Slack's names wherever they are emitted.
+//!
+//! [gemoji]: https://github.com/github/gemoji
+
+use serde_json::{Value, json};
+
+use crate::EmojiStyle;
+
+/// GitHub shortcodes whose Slack name differs, as `(github, slack)` pairs.
+const SLACK_NAMES: &[(&str, &str)] = &[
+    ("shipit", "squirrel"),
+    ("man_facepalming", "man-facepalming"),
+    ("woman_facepalming", "woman-facepalming"),
+    ("man_shrugging", "man-shrugging"),
+    ("woman_shrugging", "woman-shrugging"),
+    ("running_man", "man-running"),
+    ("running_woman", "woman-running"),
+    ("walking_man", "man-walking"),
+    ("walking_woman", "woman-walking"),
+    ("raising_hand_man", "man-raising-hand"),
+    ("raising_hand_woman", "woman-raising-hand"),
+    ("tipping_hand_man", "man-tipping-hand"),
+    ("tipping_hand_woman", "woman-tipping-hand"),
+    ("bowing_man", "man-bowing"),
+    ("bowing_woman", "woman-bowing"),
+    ("man_technologist", "male-technologist"),
+    ("woman_technologist", "female-technologist"),
+    ("man_scientist", "male-scientist"),
+    ("woman_scientist", "female-scientist"),
+];
+
+/// An emoji found in text.
+pub(crate) enum Emoji {
+    /// A shortcode, by its Slack name.
+    Shortcode(String),
+    /// A Unicode emoji.
+    Unicode(&'static str),
+}
+
+impl Emoji {
+    /// Formats the emoji as mrkdwn (or `plain_text`, where Slack renders shortcodes as well).
+    pub(crate) fn to_mrkdwn(&self) -> String {
+        match self {
+            Emoji::Shortcode(name) => format!(":{name}:"),
+            Emoji::Unicode(emoji) => emoji.to_string(),
+        }
+    }
+
+    /// Builds the `rich_text` section element for the emoji: an `emoji` element for a
+    /// shortcode, and `None` for a Unicode emoji, which is kept as text.
+    pub(crate) fn to_element(&self) -> Option<Value> {
+        match self {
+            Emoji::Shortcode(name) => Some(json!({ "type": "emoji", "name": name })),
+            Emoji::Unicode(_) => None,
+        }
+    }
+}
+
+/// A piece of text, either literal or an emoji.
+pub(crate) enum Piece<'t> {
+    Text(&'t str),
+    Emoji(Emoji),
+}
+
+/// Returns Slack's name for a GitHub shortcode.
+pub(crate) fn slack_name(name: &str) -> &str {
+    SLACK_NAMES
+        .iter()
+        .find(|(github, _)| *github == name)
+        .map_or(name, |(_, slack)| slack)
+}
+
+/// Returns the Unicode emoji for a GitHub or Slack shortcode, if it has one.
+fn unicode(name: &str) -> Option<&'static str> {
+    let github = SLACK_NAMES
+        .iter()
+        .find(|(_, slack)| *slack == name)
+        .map_or(name, |(github, _)| github);
+    emojis::get_by_shortcode(github).map(emojis::Emoji::as_str)
+}
+
+/// Cuts `text` into literal pieces and emoji, converted according to `style`.
+///
+/// A shortcode is `:name:` where the name holds lowercase letters, digits, `_`, `+`, or `-`
+/// and at least one letter (or is `+1`/`-1`), so times such as `10:30:00` are left alone. It
+/// must not directly follow a letter or digit. With [`EmojiStyle::Shortcode`], Unicode emoji
+/// are recognized too; characters that are usually text, such as `©` or `™`, are left alone.
+pub(crate) fn split(text: &str, style: EmojiStyle) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut literal = 0;
+    let mut i = 0;
+
+    while let Some(c) = text[i..].chars().next() {
+        let found = match c {
+            ':' if text[..i].chars().next_back().is_none_or(|c| !c.is_alphanumeric()) => {
+                shortcode(&text[i + 1..]).map(|name| {
+                    let emoji = match style {
+                        EmojiStyle::Unicode => unicode(name).map(Emoji::Unicode),
+                        _ => None,
+                    };
+                    (name.len() + 2, emoji.unwrap_or(Emoji::Shortcode(slack_name(name).into())))
+                })
+            }
+            c if style == EmojiStyle::Shortcode && !c.is_ascii() => unicode_emoji(&text[i..]),
+            _ => None,
+        };
+
+        match found {
+            Some((len, emoji)) => {
+                if literal < i {
+                    pieces.push(Piece::Text(&text[literal..i]));
+                }
+                pieces.push(Piece::Emoji(emoji));
+                i += len;
+                literal = i;
+            }
+            None => i += c.len_utf8(),
+        }
+    }
+    if literal < text.len() {
+        pieces.push(Piece::Text(&text[literal..]));
+    }
+    pieces
+}
+
+/// Returns the name of the shortcode at the start of `text`, which follows its opening `:`.
+fn shortcode(text: &str) -> Option<&str> {
+    let end = text.find(|c: char| !matches!(c, 'a'..='z' | '0'..='9' | '_' | '+' | '-'))?;
+    let name = &text[..end];
+    let valid = name.chars().any(|c| c.is_ascii_lowercase()) || matches!(name, "+1" | "-1");
+    (text[end..].starts_with(':') && valid).then_some(name)
+}
+
+/// Returns the length and shortcode of the Unicode emoji at the start of `text`, preferring
+/// the longest match so sequences such as flags and skin tones stay whole.
+fn unicode_emoji(text: &str) -> Option<(usize, Emoji)> {
+    let first = text.chars().next()?;
+    let ends: Vec<usize> = text.char_indices().map(|(i, c)| i + c.len_utf8()).take(10).collect();
+    let (end, emoji) = ends
+        .into_iter()
+        .rev()
+        .find_map(|end| emojis::get(&text[..end]).map(|emoji| (end, emoji)))?;
+    let presented = first >= '\u{1F000}'
+        || ('\u{2600}'..='\u{27BF}').contains(&first)
+        || text[..end].contains('\u{FE0F}');
+    let name = emoji.shortcode().filter(|_| presented)?;
+    Some((end, Emoji::Shortcode(slack_name(name).to_string())))
+}
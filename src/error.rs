@@ -0,0 +1,179 @@
+use std::fmt::{self, Display, Formatter};
+
+use markdown::{message::Place, unist};
+
+use crate::{LimitKind, Surface, Violation, limits::write_limit_exceeded};
+
+/// A `Result` whose error is this crate's [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A place in the Markdown input. Both fields are 1-indexed, as in the Markdown syntax tree.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<&unist::Point> for Position {
+    fn from(point: &unist::Point) -> Self {
+        Self { line: point.line, column: point.column }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// A range of the Markdown input, from `start` up to (but excluding) `end`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl From<&unist::Position> for Span {
+    fn from(position: &unist::Position) -> Self {
+        Self {
+            start: Position::from(&position.start),
+            end: Position::from(&position.end),
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {}", self.start, self.end)
+    }
+}
+
+/// Errors returned while converting Markdown.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The text cannot be parsed into a Markdown abstract syntax tree.
+    Parse { message: String, position: Option<Position> },
+    /// A block exceeds a Slack limit whose [`crate::OverflowPolicy`] is `Error`.
+    ///
+    /// - `kind`: the limit that was exceeded.
+    /// - `actual`: the size of the offending block (characters, rows, or columns), the number
+    ///   of blocks for [`LimitKind::Blocks`], the total for [`LimitKind::MarkdownChars`], or
+    ///   the number of tables for [`LimitKind::Tables`].
+    /// - `max`: the limit.
+    /// - `position`: where the Markdown node that produced the offending block starts. For
+    ///   [`LimitKind::Blocks`], [`LimitKind::MarkdownChars`], and [`LimitKind::Tables`], the
+    ///   node of the first block over the limit.
+    LimitExceeded { kind: LimitKind, actual: usize, max: usize, position: Option<Position> },
+    /// The syntax tree has no root node to convert.
+    Empty,
+    /// Block Kit JSON is malformed, or a block lacks a field its type requires.
+    InvalidBlock { message: String },
+    /// Block Kit JSON holds a block type this crate does not model, such as `actions`.
+    UnsupportedBlock { block_type: String },
+    /// A mention map is not valid JSON of the form [`crate::MentionMap::from_json`] expects.
+    InvalidMentionMap { message: String },
+    /// A [`crate::Message`] lacks a field its destination requires.
+    InvalidMessage { message: String },
+    /// A [`crate::View`] lacks a field it requires, or a field exceeds its limit.
+    InvalidView { message: String },
+    /// The conversion would produce a block type the surface does not allow, such as the
+    /// `markdown` blocks of [`crate::BlockMode::Markdown`] in a modal.
+    BlockNotAllowed { block_type: String, surface: Surface },
+}
+
+impl Error {
+    /// Returns where in the Markdown input the error originates, when known.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Error::Parse { position, .. } | Error::LimitExceeded { position, .. } => *position,
+            Error::Empty
+            | Error::InvalidBlock { .. }
+            | Error::UnsupportedBlock { .. }
+            | Error::InvalidMentionMap { .. }
+            | Error::InvalidMessage { .. }
+            | Error::InvalidView { .. }
+            | Error::BlockNotAllowed { .. } => None,
+        }
+    }
+}
+
+impl From<markdown::message::Message> for Error {
+    fn from(message: markdown::message::Message) -> Self {
+        let position = message.place.as_deref().map(|place| match place {
+            Place::Position(position) => Position::from(&position.start),
+            Place::Point(point) => Position::from(point),
+        });
+        Error::Parse { message: message.reason, position }
+    }
+}
+
+impl From<Violation> for Error {
+    fn from(violation: Violation) -> Self {
+        let Violation { kind, actual, max, span, .. } = violation;
+        Error::LimitExceeded {
+            kind,
+            actual,
+            max,
+            position: span.map(|span| span.start),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { message, .. } => write!(f, "Failed to parse markdown: {message}")?,
+            Error::LimitExceeded { kind, actual, max, .. } => {
+                write_limit_exceeded(f, *kind, *actual, *max)?;
+            }
+            Error::Empty => write!(f, "no input?")?,
+            Error::InvalidBlock { message } => write!(f, "invalid block: {message}")?,
+            Error::UnsupportedBlock { block_type } => {
+                write!(f, "unsupported block type: {block_type}")?;
+            }
+            Error::InvalidMentionMap { message } => write!(f, "invalid mention map: {message}")?,
+            Error::InvalidMessage { message } => write!(f, "invalid message: {message}")?,
+            Error::InvalidView { message } => write!(f, "invalid view: {message}")?,
+            Error::BlockNotAllowed { block_type, surface } => {
+                write!(f, "{block_type} blocks are not allowed on the {surface} surface")?;
+            }
+        }
+        match self.position() {
+            Some(position) => write!(f, " at {position}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A problem in the Markdown that the conversion works around instead of failing on.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Diagnostic {
+    /// A footnote reference such as `[^note]` has no definition, so it is kept as literal text.
+    /// `position` is where the text holding it starts.
+    UndefinedFootnote { label: String, position: Option<Position> },
+}
+
+impl Diagnostic {
+    /// Returns where in the Markdown input the problem is, when known.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Diagnostic::UndefinedFootnote { position, .. } => *position,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::UndefinedFootnote { label, .. } => {
+                write!(f, "footnote [^{label}] is not defined")?;
+            }
+        }
+        match self.position() {
+            Some(position) => write!(f, " at {position}"),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Footnotes: `[^note]` references are numbered in order of first use, and their definitions are
+//! collected to be rendered together at the end of the message.
+
+use std::collections::HashMap;
+
+use markdown::mdast::{Node, Text};
+
+use crate::{Diagnostic, FootnoteStyle, Position};
+
+/// A footnote definition taken out of the syntax tree.
+pub(crate) struct Footnote {
+    /// The marker its references were replaced with, e.g. `¹`.
+    pub(crate) marker: String,
+    /// The content of the definition.
+    pub(crate) children: Vec<Node>,
+}
+
+/// Takes every footnote definition out of `nodes` and replaces every reference with a text node
+/// holding its marker. Returns the referenced definitions in order of their number; definitions
+/// that are never referenced are dropped, as on GitHub.
+///
+/// The parser keeps `[^note]` without a definition as literal text, so each one left in the
+/// text is reported as [`Diagnostic::UndefinedFootnote`].
+pub(crate) fn extract(
+    nodes: &mut Vec<Node>,
+    style: FootnoteStyle,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Footnote> {
+    let mut definitions = HashMap::new();
+    take_definitions(nodes, &mut definitions);
+
+    let mut numbers = Numbers { style, numbers: HashMap::new(), order: Vec::new() };
+    numbers.replace(nodes);
+    undefined(nodes, diagnostics);
+
+    // Definitions can reference further footnotes, which get the next numbers.
+    let mut footnotes = Vec::new();
+    let mut next = 0;
+    while let Some(identifier) = numbers.order.get(next).cloned() {
+        next += 1;
+        let mut children = definitions.remove(&identifier).unwrap_or_default();
+        numbers.replace(&mut children);
+        undefined(&children, diagnostics);
+        footnotes.push(Footnote { marker: numbers.marker(next), children });
+    }
+    footnotes
+}
+
+/// Removes the footnote definitions under `nodes`, keeping the first one of each identifier.
+fn take_definitions(nodes: &mut Vec<Node>, definitions: &mut HashMap<String, Vec<Node>>) {
+    let mut i = 0;
+    while i < nodes.len() {
+        if let Node::FootnoteDefinition(definition) = &mut nodes[i] {
+            let children = std::mem::take(&mut definition.children);
+            definitions.entry(definition.identifier.clone()).or_insert(children);
+            nodes.remove(i);
+            continue;
+        }
+        if let Some(children) = nodes[i].children_mut() {
+            take_definitions(children, definitions);
+        }
+        i += 1;
+    }
+}
+
+/// The numbers given to footnote identifiers so far.
+struct Numbers {
+    style: FootnoteStyle,
+    numbers: HashMap<String, usize>,
+    /// Identifiers in order of their number.
+    order: Vec<String>,
+}
+
+impl Numbers {
+    /// Replaces the references under `nodes` with their markers, numbering new identifiers.
+    fn replace(&mut self, nodes: &mut [Node]) {
+        for node in nodes.iter_mut() {
+            if let Node::FootnoteReference(reference) = node {
+                let next = self.order.len() + 1;
+                let number = *self.numbers.entry(reference.identifier.clone()).or_insert(next);
+                if number == next {
+                    self.order.push(reference.identifier.clone());
+                }
+                *node = Node::Text(Text {
+                    value: self.marker(number),
+                    position: reference.position.take(),
+                });
+            } else if let Some(children) = node.children_mut() {
+                self.replace(children);
+            }
+        }
+    }
+
+    fn marker(&self, number: usize) -> String {
+        match self.style {
+            FootnoteStyle::Superscript => number
+                .to_string()
+                .chars()
+                .map(|digit| match digit {
+                    '1' => '¹',
+                    '2' => '²',
+                    '3' => '³',
+                    digit => char::from_u32(0x2070 + digit.to_digit(10).unwrap_or_default())
+                        .unwrap_or(digit),
+                })
+                .collect(),
+            FootnoteStyle::Bracket => format!("[{number}]"),
+        }
+    }
+}
+
+/// Reports the `[^label]`s left as literal text under `nodes`.
+fn undefined(nodes: &[Node], diagnostics: &mut Vec<Diagnostic>) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => {
+                let position =
+                    text.position.as_ref().map(|position| Position::from(&position.start));
+                let mut rest = text.value.as_str();
+                while let Some(start) = rest.find("[^") {
+                    rest = &rest[start + 2..];
+                    let Some(end) = rest.find(']') else { break };
+                    let label = &rest[..end];
+                    if !label.is_empty() && !label.contains(char::is_whitespace) {
+                        diagnostics.push(Diagnostic::UndefinedFootnote {
+                            label: label.to_string(),
+                            position,
+                        });
+                    }
+                }
+            }
+            // Code keeps `[^label]` on purpose.
+            Node::InlineCode(_) | Node::Code(_) => {}
+            _ => {
+                if let Some(children) = node.children() {
+                    undefined(children, diagnostics);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,787 @@
+use std::fmt::{self, Display, Formatter};
+
+use markdown::mdast::{AlignKind, Code, List, Node, Table};
+use serde_json::Value;
+
+use crate::{Block, ColumnSettings, Error, Result, date::Date, mrkdwn::is_line_break};
+
+/// `Markdown` is GitHub Flavored Markdown converted back from one of Slack's formats.
+///
+/// # Fields
+///
+/// - `text: String` - The GitHub Flavored Markdown.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Markdown {
+    /// Represents the converted markdown text.
+    text: String,
+}
+
+impl Markdown {
+    /// Converts Slack mrkdwn into GitHub Flavored Markdown.
+    ///
+    /// This is the reverse of [`crate::Mrkdwn::mrkdwnify_raw`]: `*bold*`, `_italic_`, `~strike~`,
+    /// `` `code` ``, code blocks, `>` quotes, and `<url|text>` links become their Markdown
+    /// counterparts, the HTML entities Slack requires (`&lt;`, `&gt;`, `&amp;`) are decoded,
+    /// and the `•`/`☐`/`☑` list glyphs become `-`, `- [ ]`, and `- [x]` list items. Code blocks
+    /// keep the quote depth and list indentation of their fences, so they stay inside nested
+    /// quotes and list items.
+    ///
+    /// Markdown has no mentions, so `<@U…|name>`, `<#C…|name>`, `<!subteam^S…|@name>`, and
+    /// `<!here>` are written as plain `@name`, `#name`, `@name`, and `@here` text (using the ID
+    /// when there is no label). Dates (`<!date^…|fallback>`) are written as their fallback text.
+    pub fn from_mrkdwn(mrkdwn: &str) -> Self {
+        let mut lines = Vec::new();
+        // The quote depth and list indentation of the open code block's fence.
+        let mut fence: Option<(usize, usize)> = None;
+        let mut quoted = 0;
+
+        for line in mrkdwn.lines() {
+            if let Some((depth, width)) = fence {
+                let (_, rest) = strip_quotes(line, depth);
+                let content = strip_indent(rest, width);
+                let prefix = format!("{}{}", "> ".repeat(depth), " ".repeat(width));
+                match content.trim_end().strip_suffix("```") {
+                    Some(code) => {
+                        if !code.is_empty() {
+                            lines.push(format!("{prefix}{}", unescape(code)));
+                        }
+                        lines.push(format!("{prefix}```"));
+                        fence = None;
+                    }
+                    None if content.is_empty() => lines.push(prefix.trim_end().to_string()),
+                    None => lines.push(format!("{prefix}{}", unescape(content))),
+                }
+                continue;
+            }
+
+            let (depth, rest) = strip_quotes(line, usize::MAX);
+            // A line quoted less deeply than the one before would continue its paragraph.
+            if depth < quoted && !rest.trim().is_empty() {
+                lines.push("> ".repeat(depth).trim_end().to_string());
+            }
+            quoted = depth;
+            let content = rest.trim_start_matches(' ');
+            let width = rest.len() - content.len();
+            if let Some(rest) = content.strip_prefix("```") {
+                let prefix = format!("{}{}", "> ".repeat(depth), " ".repeat(width));
+                lines.push(format!("{prefix}```"));
+                match rest.trim_end().strip_suffix("```") {
+                    Some(code) => {
+                        if !code.is_empty() {
+                            lines.push(format!("{prefix}{}", unescape(code)));
+                        }
+                        lines.push(format!("{prefix}```"));
+                    }
+                    None => {
+                        if !rest.is_empty() {
+                            lines.push(format!("{prefix}{}", unescape(rest)));
+                        }
+                        fence = Some((depth, width));
+                    }
+                }
+                continue;
+            }
+
+            if is_divider(line) {
+                if lines.last().is_some_and(|last: &String| !last.is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push("---".to_string());
+                continue;
+            }
+
+            lines.push(convert_line(line));
+        }
+
+        if let Some((depth, width)) = fence {
+            lines.push(format!("{}{}```", "> ".repeat(depth), " ".repeat(width)));
+        }
+
+        Self { text: lines.join("\n") }
+    }
+
+    /// Converts Block Kit blocks into GitHub Flavored Markdown, one Markdown block per Slack
+    /// block.
+    ///
+    /// Headers become `#` headings, dividers `---`, sections are converted with
+    /// [`Markdown::from_mrkdwn`], markdown blocks are kept as they are, images become
+    /// `![alt](url "title")`, and tables become GFM tables aligned by their `column_settings`.
+    /// Rich text keeps its structure: styled text becomes emphasis, strong, strikethrough, or
+    /// code, lists keep their nesting and numbering, quotes become `>` quotes, and preformatted
+    /// text becomes a code block.
+    pub fn from_blocks(blocks: &[Block]) -> Self {
+        let text = blocks.iter().map(block_to_markdown).collect::<Vec<_>>().join("\n\n");
+        Self { text }
+    }
+
+    /// Converts a Block Kit JSON payload into GitHub Flavored Markdown.
+    ///
+    /// Accepts either a message payload (`{ "blocks": [...] }`) or a bare array of blocks, as
+    /// found in Slack API responses and events. Blocks this crate does not model, such as
+    /// `actions`, are skipped; malformed blocks return [`Error::InvalidBlock`].
+    pub fn from_block_kit(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|error| Error::InvalidBlock { message: error.to_string() })?;
+        let values = match value {
+            Value::Array(values) => values,
+            Value::Object(mut payload) => match payload.remove("blocks") {
+                Some(Value::Array(values)) => values,
+                _ => return Err(Error::InvalidBlock { message: "missing blocks".to_string() }),
+            },
+            _ => return Err(Error::InvalidBlock { message: "expected blocks".to_string() }),
+        };
+
+        let mut blocks = Vec::new();
+        for value in values {
+            match Block::try_from(value) {
+                Ok(block) => blocks.push(block),
+                Err(Error::UnsupportedBlock { .. }) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(Self::from_blocks(&blocks))
+    }
+
+    /// Returns the converted GitHub Flavored Markdown.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for Markdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl From<Markdown> for String {
+    fn from(markdown: Markdown) -> Self {
+        markdown.text
+    }
+}
+
+/// Renders a single block as a Markdown block.
+fn block_to_markdown(block: &Block) -> String {
+    match block {
+        Block::Header(text) => format!("# {}", escape(text)),
+        Block::Divider => "---".to_string(),
+        Block::Section(text) => Markdown::from_mrkdwn(text).text,
+        Block::Markdown(text) => text.clone(),
+        Block::Context(texts) => texts
+            .iter()
+            .map(|text| Markdown::from_mrkdwn(text).text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Block::Image { url, alt_text, title } => match title {
+            Some(title) => {
+                format!("![{}]({url} \"{}\")", escape(alt_text), title.replace('"', "\\\""))
+            }
+            None => format!("![{}]({url})", escape(alt_text)),
+        },
+        Block::Table { column_settings, rows } => table_to_markdown(column_settings, rows),
+        Block::RichText(elements) => {
+            // Consecutive lists are one Markdown list whose nesting Slack split up.
+            let mut markdown = String::new();
+            let mut previous: Option<&Value> = None;
+            for element in elements {
+                if let Some(previous) = previous {
+                    let lists = [previous, element]
+                        .iter()
+                        .all(|element| element["type"] == "rich_text_list");
+                    markdown.push_str(if lists { "\n" } else { "\n\n" });
+                }
+                markdown.push_str(&rich_text_to_markdown(element));
+                previous = Some(element);
+            }
+            markdown
+        }
+    }
+}
+
+/// Renders a table as a GFM table, taking the first row as the header row.
+fn table_to_markdown(column_settings: &[ColumnSettings], rows: &[Vec<Value>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let row = |cells: &[Value]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| cells.get(i).map(cell_to_markdown).unwrap_or_default())
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let delimiters: Vec<&str> = (0..columns)
+        .map(|i| match column_settings.get(i).and_then(|settings| settings.align.as_deref()) {
+            Some("left") => ":---",
+            Some("center") => ":---:",
+            Some("right") => "---:",
+            _ => "---",
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    if let Some((header, body)) = rows.split_first() {
+        lines.push(row(header));
+        lines.push(format!("| {} |", delimiters.join(" | ")));
+        lines.extend(body.iter().map(|cells| row(cells)));
+    }
+    lines.join("\n")
+}
+
+/// Renders a table cell, a `raw_text` or `rich_text` element, as inline Markdown that fits on
+/// a single table row.
+fn cell_to_markdown(cell: &Value) -> String {
+    let text = match cell["type"].as_str() {
+        Some("rich_text") => cell["elements"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|element| inline_elements(&element["elements"]))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => escape(cell["text"].as_str().unwrap_or_default()),
+    };
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Renders a top-level rich text element: a section, list, quote, or preformatted block.
+fn rich_text_to_markdown(element: &Value) -> String {
+    match element["type"].as_str() {
+        Some("rich_text_list") => {
+            let indent = "    ".repeat(element["indent"].as_u64().unwrap_or(0) as usize);
+            let ordered = element["style"].as_str() == Some("ordered");
+            let offset = element["offset"].as_u64().unwrap_or(0);
+            element["elements"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .map(|(i, item)| {
+                    let marker = if ordered {
+                        format!("{}. ", offset + i as u64 + 1)
+                    } else {
+                        "- ".to_string()
+                    };
+                    format!("{indent}{marker}{}", inline_elements(&item["elements"]))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Some("rich_text_quote") => inline_elements(&element["elements"])
+            .lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some("rich_text_preformatted") => {
+            let code: String = element["elements"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|element| match element["type"].as_str() {
+                    Some("link") => element["text"]
+                        .as_str()
+                        .or_else(|| element["url"].as_str())
+                        .unwrap_or_default(),
+                    _ => element["text"].as_str().unwrap_or_default(),
+                })
+                .collect();
+            format!("```\n{}\n```", code.trim_end_matches('\n'))
+        }
+        _ => inline_elements(&element["elements"]),
+    }
+}
+
+/// Renders the inline elements of a rich text section. Adjacent text elements with the same
+/// style are merged first, so Slack's per-run splitting does not produce `**a****b**`.
+fn inline_elements(elements: &Value) -> String {
+    let mut merged: Vec<Value> = Vec::new();
+    for element in elements.as_array().into_iter().flatten() {
+        if let Some(last) = merged.last_mut()
+            && last["type"] == "text"
+            && element["type"] == "text"
+            && last["style"] == element["style"]
+        {
+            let text = format!(
+                "{}{}",
+                last["text"].as_str().unwrap_or_default(),
+                element["text"].as_str().unwrap_or_default()
+            );
+            last["text"] = Value::String(text);
+            continue;
+        }
+        merged.push(element.clone());
+    }
+    merged.iter().map(inline_element).collect()
+}
+
+/// Renders a single inline rich text element.
+fn inline_element(element: &Value) -> String {
+    let str = |key: &str| element[key].as_str().unwrap_or_default();
+    match element["type"].as_str() {
+        Some("text") => styled(str("text"), &element["style"]),
+        Some("link") => {
+            let text = match element["text"].as_str() {
+                Some(text) => escape(text),
+                None => escape(str("url")),
+            };
+            let url = str("url");
+            let link = if url.contains([' ', '(', ')']) {
+                format!("[{text}](<{url}>)")
+            } else {
+                format!("[{text}]({url})")
+            };
+            wrap(&link, &element["style"])
+        }
+        Some("emoji") => format!(":{}:", str("name")),
+        Some("user") => format!("@{}", str("user_id")),
+        Some("usergroup") => format!("@{}", str("usergroup_id")),
+        Some("channel") => format!("#{}", str("channel_id")),
+        Some("broadcast") => format!("@{}", str("range")),
+        Some("date") => match element["fallback"].as_str() {
+            Some(fallback) => escape(fallback),
+            None => element["timestamp"].to_string(),
+        },
+        _ => escape(str("text")),
+    }
+}
+
+/// Renders styled text. Code spans keep their content verbatim, and whitespace at either end is
+/// kept outside the markers, since Markdown emphasis cannot start or end with a space.
+fn styled(text: &str, style: &Value) -> String {
+    let content = text.trim();
+    if content.is_empty() {
+        return text.to_string();
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    let content = if style["code"] == true {
+        let fence = if content.contains('`') { "``" } else { "`" };
+        format!("{fence}{content}{fence}")
+    } else {
+        escape(content)
+    };
+    format!("{leading}{}{trailing}", wrap(&content, style))
+}
+
+/// Wraps inline Markdown in the emphasis markers of a rich text style.
+fn wrap(text: &str, style: &Value) -> String {
+    let mut text = text.to_string();
+    for (key, marker) in [("strike", "~~"), ("italic", "_"), ("bold", "**")] {
+        if style[key] == true {
+            text = format!("{marker}{text}{marker}");
+        }
+    }
+    text
+}
+
+/// Returns whether a mrkdwn line is a hand-drawn divider such as `----------`.
+fn is_divider(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3 && line.chars().all(|c| c == '-')
+}
+
+/// Converts a single mrkdwn line outside code blocks: the quote markers, then the list marker,
+/// then the inline content.
+fn convert_line(line: &str) -> String {
+    let (depth, line) = strip_quotes(line, usize::MAX);
+    let quote = "> ".repeat(depth);
+
+    let content = line.trim_start_matches(' ');
+    let indent = &line[..line.len() - content.len()];
+    let (marker, content) = match list_marker(content) {
+        Some((marker, rest)) => (marker, rest.trim_start_matches(' ')),
+        None => (String::new(), content),
+    };
+
+    let mut inline = inline(&content.chars().collect::<Vec<_>>());
+    if marker.is_empty() {
+        inline = escape_block_start(&inline);
+    }
+    format!("{quote}{indent}{marker}{inline}")
+}
+
+/// Strips up to `max` leading quote markers (`>` or `&gt;`, each with the space after it),
+/// returning how many were stripped and the rest of the line.
+fn strip_quotes(mut line: &str, max: usize) -> (usize, &str) {
+    let mut depth = 0;
+    while depth < max
+        && let Some(rest) = line.strip_prefix("&gt;").or_else(|| line.strip_prefix('>'))
+    {
+        line = rest.strip_prefix(' ').unwrap_or(rest);
+        depth += 1;
+    }
+    (depth, line)
+}
+
+/// Strips up to `width` leading spaces, the indentation of the fence a code line belongs to.
+fn strip_indent(line: &str, width: usize) -> &str {
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    &line[spaces.min(width)..]
+}
+
+/// Recognizes the list markers mrkdwn text uses, returning the Markdown marker and the rest of
+/// the line.
+fn list_marker(line: &str) -> Option<(String, &str)> {
+    for (glyph, marker) in
+        [("•", "- "), ("◦", "- "), ("▪", "- "), ("☐", "- [ ] "), ("☑", "- [x] "), ("- ", "- ")]
+    {
+        if let Some(rest) = line.strip_prefix(glyph)
+            && (rest.is_empty() || rest.starts_with(' ') || glyph.ends_with(' '))
+        {
+            return Some((marker.to_string(), rest));
+        }
+    }
+
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = line[digits..].strip_prefix(". ")?;
+    (digits > 0).then(|| (format!("{}. ", &line[..digits]), rest))
+}
+
+/// Escapes text at the start of a line that Markdown would otherwise read as a block marker.
+fn escape_block_start(text: &str) -> String {
+    if text.starts_with('#') || text.starts_with("- ") || text.starts_with("+ ") {
+        return format!("\\{text}");
+    }
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 && (text[digits..].starts_with(". ") || text[digits..].starts_with(") ")) {
+        return format!("{}\\{}", &text[..digits], &text[digits..]);
+    }
+    text.to_string()
+}
+
+/// Converts inline mrkdwn: formatting, code spans, and `<...>` links and mentions.
+fn inline(chars: &[char]) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '`' => {
+                if let Some(end) = find(chars, i + 1, |c| c == '`') {
+                    let code: String = chars[i + 1..end].iter().collect();
+                    result.push_str(&format!("`{}`", unescape(&code)));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '<' => {
+                if let Some(end) = find(chars, i + 1, |c| c == '>') {
+                    let token: String = chars[i + 1..end].iter().collect();
+                    result.push_str(&angle_token(&token));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '*' | '_' | '~' if opens(chars, i) => {
+                if let Some(end) = closes(chars, i) {
+                    let inner = inline(&chars[i + 1..end]);
+                    let marker = match c {
+                        '*' => "**",
+                        '_' => "_",
+                        _ => "~~",
+                    };
+                    result.push_str(&format!("{marker}{inner}{marker}"));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '&' => {
+                let rest: String = chars[i..chars.len().min(i + 5)].iter().collect();
+                if let Some((entity, decoded)) = [("&lt;", '<'), ("&gt;", '>'), ("&amp;", '&')]
+                    .into_iter()
+                    .find(|(entity, _)| rest.starts_with(entity))
+                {
+                    result.push_str(&escape_char(decoded));
+                    i += entity.len();
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        result.push_str(&escape_char(c));
+        i += 1;
+    }
+
+    result
+}
+
+/// Converts the content of a `<...>` token: a link, a mention, or a special command.
+fn angle_token(token: &str) -> String {
+    let (target, label) = match token.split_once('|') {
+        Some((target, label)) => (target, Some(label)),
+        None => (token, None),
+    };
+    let label_or = |fallback: String| match label {
+        Some(label) => escape(&unescape(label)),
+        None => fallback,
+    };
+
+    if let Some(user) = target.strip_prefix('@') {
+        return format!("@{}", label_or(user.to_string()));
+    }
+    if let Some(channel) = target.strip_prefix('#') {
+        return format!("#{}", label_or(channel.to_string()));
+    }
+    if let Some(command) = target.strip_prefix('!') {
+        return match command.split_once('^') {
+            Some(("subteam", id)) => {
+                let name = label_or(format!("@{id}"));
+                if name.starts_with('@') { name } else { format!("@{name}") }
+            }
+            Some(_) => label_or(String::new()),
+            None => label_or(format!("@{command}")),
+        };
+    }
+
+    let url = unescape(target);
+    match label {
+        Some(label) => {
+            let text = inline(&label.chars().collect::<Vec<_>>());
+            if url.contains([' ', '(', ')']) {
+                format!("[{text}](<{url}>)")
+            } else {
+                format!("[{text}]({url})")
+            }
+        }
+        None => format!("<{url}>"),
+    }
+}
+
+/// Returns whether the formatting marker at `i` can open a span: it starts the text or follows
+/// a non-alphanumeric character, and is followed by a non-space character.
+fn opens(chars: &[char], i: usize) -> bool {
+    let before = i.checked_sub(1).map(|j| chars[j]);
+    let after = chars.get(i + 1);
+    before.is_none_or(|c| !c.is_alphanumeric())
+        && after.is_some_and(|c| !c.is_whitespace() && *c != chars[i])
+}
+
+/// Finds the marker closing the span opened at `start`: the same character, preceded by a
+/// non-space character, and followed by the end of the text or a non-alphanumeric character.
+fn closes(chars: &[char], start: usize) -> Option<usize> {
+    let marker = chars[start];
+    (start + 2..chars.len()).find(|&j| {
+        chars[j] == marker
+            && !chars[j - 1].is_whitespace()
+            && chars.get(j + 1).is_none_or(|c| !c.is_alphanumeric())
+    })
+}
+
+/// Finds the first character at or after `start` matching `predicate`.
+fn find(chars: &[char], start: usize, predicate: impl Fn(char) -> bool) -> Option<usize> {
+    (start..chars.len()).find(|&j| predicate(chars[j]))
+}
+
+/// Decodes the three HTML entities Slack requires in mrkdwn text.
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+/// Escapes the characters Markdown would read as inline syntax.
+fn escape(text: &str) -> String {
+    text.chars().map(escape_char).collect()
+}
+
+fn escape_char(c: char) -> String {
+    match c {
+        '\\' | '`' | '*' | '_' | '~' | '[' | ']' | '<' | '>' => format!("\\{c}"),
+        c => c.to_string(),
+    }
+}
+
+/// Renders nodes of a Markdown syntax tree as normalized GitHub Flavored Markdown, for
+/// `markdown` blocks: emphasis as `*`, lists as `-` and `1.`, code as fenced blocks, and links
+/// and images inline. Definitions and HTML render as nothing, except `<br>` tags, which become
+/// line breaks, and `<time>` tags, which become the fallback text of their date.
+pub(crate) fn nodes_to_markdown(nodes: &[Node]) -> String {
+    flow(nodes, "\n\n")
+}
+
+/// Renders block-level nodes, separating them with `separator`.
+fn flow(nodes: &[Node], separator: &str) -> String {
+    nodes
+        .iter()
+        .filter_map(flow_node)
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn flow_node(node: &Node) -> Option<String> {
+    Some(match node {
+        Node::Paragraph(n) => phrasing(&n.children)
+            .lines()
+            .map(escape_block_start)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Node::Heading(n) => {
+            let text = phrasing(&n.children).replace("\\\n", " ").replace('\n', " ");
+            format!("{} {}", "#".repeat(n.depth.into()), text.trim())
+        }
+        Node::ThematicBreak(_) => "---".to_string(),
+        Node::Blockquote(n) => prefix_lines(&flow(&n.children, "\n\n"), "> ", "> "),
+        Node::List(n) => list(n),
+        Node::Code(n) => code(n),
+        Node::Table(n) => table(n),
+        Node::FootnoteDefinition(n) => {
+            let label = n.label.as_deref().unwrap_or(&n.identifier);
+            prefix_lines(&flow(&n.children, "\n\n"), &format!("[^{label}]: "), "    ")
+        }
+        Node::Html(n) => Date::from_html(&n.value)?.fallback(),
+        _ => return None,
+    })
+}
+
+/// Prefixes the first line of `text` with `first` and every other non-empty line with `rest`.
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => format!("{first}{line}"),
+            _ if line.is_empty() => rest.trim_end().to_string(),
+            _ => format!("{rest}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn list(list: &List) -> String {
+    let mut number = list.start.unwrap_or(1);
+    let items: Vec<String> = list
+        .children
+        .iter()
+        .filter_map(|item| match item {
+            Node::ListItem(item) => Some(item),
+            _ => None,
+        })
+        .map(|item| {
+            let marker = if list.ordered { format!("{number}.") } else { "-".to_string() };
+            number += 1;
+            let task = match item.checked {
+                Some(true) => "[x] ",
+                Some(false) => "[ ] ",
+                None => "",
+            };
+            let separator = if list.spread || item.spread { "\n\n" } else { "\n" };
+            let content = flow(&item.children, separator);
+            let indent = " ".repeat(marker.len() + 1);
+            prefix_lines(&content, &format!("{marker} {task}"), &indent)
+        })
+        .collect();
+    items.join(if list.spread { "\n\n" } else { "\n" })
+}
+
+/// Renders a code block fenced by more backticks than any run of them in the code.
+fn code(code: &Code) -> String {
+    let fence = "`".repeat(longest_run(&code.value, '`').max(2) + 1);
+    let info = [code.lang.as_deref(), code.meta.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("{fence}{info}\n{}\n{fence}", code.value)
+}
+
+fn table(table: &Table) -> String {
+    let rows: Vec<String> = table
+        .children
+        .iter()
+        .filter_map(Node::children)
+        .map(|cells| {
+            let cells: Vec<String> = cells
+                .iter()
+                .map(|cell| {
+                    let text = phrasing(cell.children().map_or(&[][..], Vec::as_slice));
+                    text.replace('|', "\\|").replace("\\\n", " ").replace('\n', " ")
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        })
+        .collect();
+    let delimiters: Vec<&str> = table
+        .align
+        .iter()
+        .map(|align| match align {
+            AlignKind::Left => ":---",
+            AlignKind::Center => ":---:",
+            AlignKind::Right => "---:",
+            AlignKind::None => "---",
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    if let Some((header, body)) = rows.split_first() {
+        lines.push(header.clone());
+        lines.push(format!("| {} |", delimiters.join(" | ")));
+        lines.extend(body.iter().cloned());
+    }
+    lines.join("\n")
+}
+
+/// Renders inline nodes.
+fn phrasing(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(n) => escape_text(&n.value),
+            Node::Strong(n) => format!("**{}**", phrasing(&n.children)),
+            Node::Emphasis(n) => format!("*{}*", phrasing(&n.children)),
+            Node::Delete(n) => format!("~~{}~~", phrasing(&n.children)),
+            Node::InlineCode(n) => {
+                let fence = "`".repeat(longest_run(&n.value, '`') + 1);
+                let pad = n.value.starts_with('`') || n.value.ends_with('`');
+                let pad = if pad { " " } else { "" };
+                format!("{fence}{pad}{}{pad}{fence}", n.value)
+            }
+            Node::Break(_) => "\\\n".to_string(),
+            Node::Link(n) => {
+                format!("[{}]({})", phrasing(&n.children), destination(&n.url, n.title.as_deref()))
+            }
+            Node::Image(n) => {
+                format!("![{}]({})", escape(&n.alt), destination(&n.url, n.title.as_deref()))
+            }
+            Node::LinkReference(n) => phrasing(&n.children),
+            Node::ImageReference(n) => escape(&n.alt),
+            Node::FootnoteReference(n) => {
+                format!("[^{}]", n.label.as_deref().unwrap_or(&n.identifier))
+            }
+            Node::Html(n) if is_line_break(&n.value) => "\\\n".to_string(),
+            Node::Html(n) => Date::from_html(&n.value).map(Date::fallback).unwrap_or_default(),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+/// Renders the `(url "title")` part of a link or image, wrapping the URL in `<>` when it has
+/// spaces or parentheses.
+fn destination(url: &str, title: Option<&str>) -> String {
+    let url = if url.contains([' ', '(', ')']) {
+        format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+    } else {
+        url.to_string()
+    };
+    match title {
+        Some(title) => format!("{url} \"{}\"", title.replace('"', "\\\"")),
+        None => url,
+    }
+}
+
+/// Escapes the characters Markdown would read as inline syntax, leaving `_` inside words,
+/// where it does not emphasize.
+fn escape_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let intraword = c == '_'
+                && i > 0
+                && chars[i - 1].is_alphanumeric()
+                && chars.get(i + 1).is_some_and(|c| c.is_alphanumeric());
+            if intraword { c.to_string() } else { escape_char(c) }
+        })
+        .collect()
+}
+
+/// Returns the length of the longest run of `c` in `text`.
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|other| other != c).map(str::len).max().unwrap_or_default()
+}
@@ -1,8 +1,30 @@
-pub use block::Block;
-pub use mrkdwn::Mrkdwn;
+pub use block::{Block, ColumnSettings};
+pub use error::{Diagnostic, Error, Position, Result, Span};
+pub use gfm::Markdown;
+pub use limits::{Limit, LimitKind, Limits, OverflowPolicy, Surface, Truncation, Violation};
+pub use mention::{Broadcast, Mention, MentionMap, MentionResolver};
+pub use message::{Destination, Message, ResponseType};
+pub use mrkdwn::{BlockMode, Conversion, Mrkdwn};
+pub use options::{
+    ColumnWrap, ConvertOptions, ConvertOptionsBuilder, DividerStyle, EmojiStyle, FootnoteStyle,
+    HeadingStyle, ImageStyle, NumberStyle, TableCells, TableFallback, TextTableStyle,
+};
+pub use view::View;
 
 mod block;
+mod date;
+mod emoji;
+mod error;
+mod footnote;
+mod gfm;
+mod limits;
+mod mention;
+mod message;
 mod mrkdwn;
+mod options;
+mod reference;
+mod text;
+mod view;
 
 #[cfg(test)]
 mod test {
@@ -105,7 +127,7 @@ mod test {
         test!(
             nested_lists,
             "- First\n    - Second\n        - Third\n    - Fourth\n        - Fifth\n        - Sixth\n- Seventh",
-            r#"{ "blocks": [ { "text": { "text": "•   First\n    •   Second\n        •   Third\n    •   Fourth\n        •   Fifth\n        •   Sixth\n\n•   Seventh\n\n", "type": "mrkdwn" }, "type": "section" } ] }"#
+            r#"{ "blocks": [ { "text": { "text": "•   First\n    •   Second\n        •   Third\n    •   Fourth\n        •   Fifth\n        •   Sixth\n•   Seventh\n\n", "type": "mrkdwn" }, "type": "section" } ] }"#
         );
         test!(thematic_breaks, "---", r#"{ "blocks": [ { "type": "divider" } ] }"#);
         test!(
@@ -170,83 +192,2878 @@ console.log('Hello, mrkdwn!')
 
 Another paragraph.
 "#,
-            r#"{ "blocks": [ { "text": { "emoji": true, "text": "Heading 1", "type": "plain_text" }, "type": "header" }, { "type": "divider" }, { "text": { "emoji": true, "text": "Heading 2", "type": "plain_text" }, "type": "header" }, { "text": { "text": "*Heading 3*", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "Hello, ~Markdown~ *mrkdwn*! and _markdown_.\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "`mrkdwn` is text formatting markup style in <https://slack.com/|Slack>.\n", "type": "mrkdwn" }, "type": "section" }, { "type": "divider" }, { "text": { "text": "•   First\n    •   Second\n        •   Third\n    •   Fourth\n        •   Fifth\n        •   Sixth\n\n•   Seventh\n\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "1.  Ordered list 1\n    •   Ordered list 1-1\n        •   Ordered list 1-2\n\n2.  Ordered list 2\n    1.  Ordered list 2-1\n    2.  Ordered list 2-2\n\n3.  Ordered list 3\n\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "> _This is blockquote._\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "```\nconsole.log('Hello, mrkdwn!')\n```\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "Another paragraph.\n", "type": "mrkdwn" }, "type": "section" } ] }"#
+            r#"{ "blocks": [ { "text": { "emoji": true, "text": "Heading 1", "type": "plain_text" }, "type": "header" }, { "type": "divider" }, { "text": { "emoji": true, "text": "Heading 2", "type": "plain_text" }, "type": "header" }, { "text": { "text": "*Heading 3*", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "Hello, ~Markdown~ *mrkdwn*! and _markdown_.\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "`mrkdwn` is text formatting markup style in <https://slack.com/|Slack>.\n", "type": "mrkdwn" }, "type": "section" }, { "type": "divider" }, { "text": { "text": "•   First\n    •   Second\n        •   Third\n    •   Fourth\n        •   Fifth\n        •   Sixth\n•   Seventh\n\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "1.  Ordered list 1\n    •   Ordered list 1-1\n        •   Ordered list 1-2\n2.  Ordered list 2\n    1.  Ordered list 2-1\n    2.  Ordered list 2-2\n3.  Ordered list 3\n\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "> _This is blockquote._\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "```\nconsole.log('Hello, mrkdwn!')\n```\n", "type": "mrkdwn" }, "type": "section" }, { "text": { "text": "Another paragraph.\n", "type": "mrkdwn" }, "type": "section" } ] }"#
         );
     }
 
+    mod from_mrkdwn {
+        use crate::{Markdown, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Markdown::from_mrkdwn($input).as_str(), $expected);
+                }
+            };
+        }
+
+        test!(bold, "*bold*", "**bold**");
+        test!(italic, "_italic_", "_italic_");
+        test!(strikethrough, "~strike~", "~~strike~~");
+        test!(nested, "*bold _and italic_*", "**bold _and italic_**");
+        test!(intraword_markers, "snake_case_name and 2*3*4", "snake\\_case\\_name and 2\\*3\\*4");
+        test!(entities, "1 &lt; 2 &gt; 0 &amp;&amp; true", "1 \\< 2 \\> 0 && true");
+        test!(inline_code, "`a &lt; b` and `*x*`", "`a < b` and `*x*`");
+        test!(code_block, "```\nif a &lt; b {}\n```", "```\nif a < b {}\n```");
+        test!(code_block_on_one_line, "```let x = 1;```", "```\nlet x = 1;\n```");
+        test!(link, "<https://slack.com/|Slack>", "[Slack](https://slack.com/)");
+        test!(
+            link_with_formatting,
+            "<https://slack.com/|*Slack*>",
+            "[**Slack**](https://slack.com/)"
+        );
+        test!(bare_link, "<https://slack.com/>", "<https://slack.com/>");
+        test!(link_with_entities, "<https://x.com/?a=1&amp;b=2|x>", "[x](https://x.com/?a=1&b=2)");
+        test!(user_mention, "hi <@U123|alice> and <@U456>", "hi @alice and @U456");
+        test!(channel_mention, "see <#C123|deploys> or <#C456>", "see #deploys or #C456");
+        test!(broadcast, "<!here> <!channel> <!everyone>", "@here @channel @everyone");
+        test!(usergroup, "<!subteam^S123|@oncall> <!subteam^S456>", "@oncall @S456");
+        test!(date, "<!date^1392734382^{date_short}|Feb 18, 2014>", "Feb 18, 2014");
+        test!(quote, "&gt; quoted *text*", "> quoted **text**");
+        test!(raw_quote, "> quoted", "> quoted");
+        test!(bullets, "•   First\n•   Second", "- First\n- Second");
+        test!(task_list, "☐   Todo\n☑   Done", "- [ ] Todo\n- [x] Done");
+        test!(ordered_list, "1.  First\n2.  Second", "1. First\n2. Second");
+        test!(nested_list, "•   First\n    •   Second", "- First\n    - Second");
+        test!(divider, "Before\n----------\nAfter", "Before\n\n---\nAfter");
+        test!(nested_quote, "> quoted\n> > nested", "> quoted\n> > nested");
+        test!(
+            code_block_in_quote,
+            "> ```\n> if a &lt; b {}\n>   *x*\n> ```",
+            "> ```\n> if a < b {}\n>   *x*\n> ```"
+        );
+        test!(
+            code_block_in_list,
+            "•   Run:\n    ```\n    cargo test\n\n    cargo build\n    ```\n•   Done",
+            "- Run:\n    ```\n    cargo test\n\n    cargo build\n    ```\n- Done"
+        );
+        test!(
+            escapes_block_markers,
+            "# not a heading\n+ not a list",
+            "\\# not a heading\n\\+ not a list"
+        );
+
+        fn mrkdwn(markdown: &str) -> String {
+            Mrkdwn::from(markdown).mrkdwnify_raw().unwrap()
+        }
+
+        #[test]
+        fn round_trips_mrkdwnify_output() {
+            let inputs = [
+                "Hello, ~~Markdown~~ **mrkdwn**! and _markdown_.",
+                "`mrkdwn` is text formatting markup style in [Slack](https://slack.com/).",
+                "1 < 2 > 0 & \"quoted\"",
+                "This is unquoted.\n> This is quoted.",
+                "> This is quoted.\n\nThis is unquoted.",
+                "```\nconsole.log('Hello, mrkdwn!')\n```",
+                "- First\n- Second\n- Third",
+                "1. First\n1. Second\n1. Third",
+                "- [ ] First\n- [x] Second",
+                "Text[^note].\n\n[^note]: The body.",
+                "Before\n\n---\n\nAfter",
+                "Use snake_case and 2*3*4.",
+                "> outer\n>\n> > inner\n> > more\n>\n> back",
+                "> quoted\n>\n> ```\n> let a = 1 < 2;\n>   b\n> ```",
+                "- a\n    - b\n        - c\n- d",
+                "- Run:\n  ```\n  cargo test\n\n  cargo build\n  ```\n- Done",
+                "- Results:\n\n    ```\n    a | b\n    ```\n\n- Next",
+            ];
+            for input in inputs {
+                let mrkdwn = mrkdwn(input);
+                let markdown = Markdown::from_mrkdwn(&mrkdwn);
+                assert_eq!(self::mrkdwn(markdown.as_str()), mrkdwn, "{input:?} -> {markdown}");
+            }
+        }
+    }
+
+    mod from_block_kit {
+        use serde_json::{Value, json};
+
+        use crate::{Block, Error, Markdown, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $blocks:tt, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    let json = json!({ "blocks": $blocks }).to_string();
+                    assert_eq!(Markdown::from_block_kit(&json).unwrap().as_str(), $expected);
+                }
+            };
+        }
+
+        test!(
+            header_divider_section,
+            [
+                { "type": "header", "text": { "type": "plain_text", "text": "Title" } },
+                { "type": "divider" },
+                { "type": "section", "text": { "type": "mrkdwn", "text": "*bold* &amp; <https://x.com|x>" } },
+            ],
+            "# Title\n\n---\n\n**bold** & [x](https://x.com)"
+        );
+        test!(
+            plain_text_section,
+            [{ "type": "section", "text": { "type": "plain_text", "text": "a < b & c" } }],
+            "a \\< b & c"
+        );
+        test!(
+            section_fields,
+            [{
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": "Status" },
+                "fields": [{ "type": "mrkdwn", "text": "*Up*" }, { "type": "plain_text", "text": "1h" }],
+            }],
+            "Status\n**Up**\n1h"
+        );
+        test!(
+            image,
+            [{
+                "type": "image",
+                "image_url": "https://x.com/a.png",
+                "alt_text": "A [cat]",
+                "title": { "type": "plain_text", "text": "Cat" },
+            }],
+            "![A \\[cat\\]](https://x.com/a.png \"Cat\")"
+        );
+        test!(
+            table,
+            [{
+                "type": "table",
+                "column_settings": [null, { "align": "center" }, { "align": "right" }],
+                "rows": [
+                    [
+                        { "type": "raw_text", "text": "Name" },
+                        { "type": "raw_text", "text": "Kind" },
+                        { "type": "raw_text", "text": "Count" },
+                    ],
+                    [
+                        { "type": "raw_text", "text": "a|b" },
+                        {
+                            "type": "rich_text",
+                            "elements": [{
+                                "type": "rich_text_section",
+                                "elements": [{ "type": "text", "text": "x", "style": { "bold": true } }],
+                            }],
+                        },
+                        { "type": "raw_text", "text": "2" },
+                    ],
+                ],
+            }],
+            "| Name | Kind | Count |\n| --- | :---: | ---: |\n| a\\|b | **x** | 2 |"
+        );
+        test!(
+            rich_text_styles,
+            [{
+                "type": "rich_text",
+                "elements": [{
+                    "type": "rich_text_section",
+                    "elements": [
+                        { "type": "text", "text": "bold ", "style": { "bold": true } },
+                        { "type": "text", "text": "still", "style": { "bold": true } },
+                        { "type": "text", "text": " and " },
+                        { "type": "text", "text": "it", "style": { "italic": true, "strike": true } },
+                        { "type": "text", "text": " " },
+                        { "type": "text", "text": "a*b", "style": { "code": true } },
+                        { "type": "text", "text": " " },
+                        { "type": "link", "url": "https://x.com", "text": "x" },
+                        { "type": "text", "text": " " },
+                        { "type": "emoji", "name": "wave" },
+                    ],
+                }],
+            }],
+            "**bold still** and _~~it~~_ `a*b` [x](https://x.com) :wave:"
+        );
+        test!(
+            rich_text_mentions,
+            [{
+                "type": "rich_text",
+                "elements": [{
+                    "type": "rich_text_section",
+                    "elements": [
+                        { "type": "user", "user_id": "U1" },
+                        { "type": "text", "text": " " },
+                        { "type": "channel", "channel_id": "C1" },
+                        { "type": "text", "text": " " },
+                        { "type": "broadcast", "range": "here" },
+                        { "type": "text", "text": " " },
+                        { "type": "date", "timestamp": 1392734382, "format": "{date_short}", "fallback": "Feb 18, 2014" },
+                    ],
+                }],
+            }],
+            "@U1 #C1 @here Feb 18, 2014"
+        );
+        test!(
+            rich_text_lists,
+            [{
+                "type": "rich_text",
+                "elements": [
+                    {
+                        "type": "rich_text_list",
+                        "style": "ordered",
+                        "indent": 0,
+                        "elements": [
+                            { "type": "rich_text_section", "elements": [{ "type": "text", "text": "First" }] },
+                        ],
+                    },
+                    {
+                        "type": "rich_text_list",
+                        "style": "bullet",
+                        "indent": 1,
+                        "elements": [
+                            { "type": "rich_text_section", "elements": [{ "type": "text", "text": "Nested" }] },
+                        ],
+                    },
+                    {
+                        "type": "rich_text_list",
+                        "style": "ordered",
+                        "indent": 0,
+                        "offset": 1,
+                        "elements": [
+                            { "type": "rich_text_section", "elements": [{ "type": "text", "text": "Second" }] },
+                        ],
+                    },
+                ],
+            }],
+            "1. First\n    - Nested\n2. Second"
+        );
+        test!(
+            rich_text_quote_and_preformatted,
+            [{
+                "type": "rich_text",
+                "elements": [
+                    {
+                        "type": "rich_text_quote",
+                        "elements": [{ "type": "text", "text": "line 1\nline 2" }],
+                    },
+                    {
+                        "type": "rich_text_preformatted",
+                        "elements": [{ "type": "text", "text": "let a = *b;" }],
+                    },
+                ],
+            }],
+            "> line 1\n> line 2\n\n```\nlet a = *b;\n```"
+        );
+        test!(
+            skips_unsupported_blocks,
+            [
+                { "type": "actions", "elements": [] },
+                { "type": "section", "text": { "type": "mrkdwn", "text": "kept" } },
+            ],
+            "kept"
+        );
+
+        #[test]
+        fn accepts_bare_array() {
+            let json = r#"[{ "type": "divider" }]"#;
+            assert_eq!(Markdown::from_block_kit(json).unwrap().as_str(), "---");
+        }
+
+        #[test]
+        fn rejects_malformed_blocks() {
+            assert!(matches!(
+                Markdown::from_block_kit(r#"{ "blocks": [{ "type": "header" }] }"#),
+                Err(Error::InvalidBlock { .. })
+            ));
+            assert!(matches!(Markdown::from_block_kit("{"), Err(Error::InvalidBlock { .. })));
+        }
+
+        #[test]
+        fn unsupported_block_type() {
+            assert_eq!(
+                Block::try_from(json!({ "type": "actions", "elements": [] })),
+                Err(Error::UnsupportedBlock { block_type: "actions".to_string() })
+            );
+        }
+
+        #[test]
+        fn round_trips_blocks() {
+            let markdown =
+                "# Title\n\nSome **bold** text.\n\n| a | b |\n| :--- | ---: |\n| 1 | 2 |";
+            let blocks = Mrkdwn::from(markdown).blockify().unwrap();
+            let values: Vec<Value> = blocks.iter().cloned().map(Value::from).collect();
+            let parsed: Vec<Block> = values
+                .into_iter()
+                .map(|value| Block::try_from(value).unwrap())
+                .collect();
+            assert_eq!(parsed, blocks);
+            // Headings are followed by a divider when converted to blocks.
+            assert_eq!(
+                Markdown::from_blocks(&parsed).as_str(),
+                markdown.replacen("\n\n", "\n\n---\n\n", 1)
+            );
+        }
+    }
+
     mod limits {
         use crate::Mrkdwn;
 
         #[test]
-        fn header_at_150_chars_is_ok() {
-            let input = format!("# {}", "a".repeat(150));
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        fn header_at_150_chars_is_ok() {
+            let input = format!("# {}", "a".repeat(150));
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        }
+
+        #[test]
+        fn header_over_150_chars_is_err() {
+            let input = format!("# {}", "a".repeat(151));
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        }
+
+        #[test]
+        fn section_at_3000_chars_is_ok() {
+            // A 2999-char paragraph renders as 3000 chars (trailing newline included).
+            let input = "a".repeat(2999);
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        }
+
+        #[test]
+        fn section_over_3000_chars_is_err() {
+            let input = "a".repeat(3000);
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        }
+
+        #[test]
+        fn alt_text_over_2000_chars_is_err() {
+            let input = format!("![{}](https://example.com/x.png)", "a".repeat(2001));
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        }
+
+        #[test]
+        fn table_over_100_rows_is_err() {
+            let mut input = String::from("| h |\n|---|\n");
+            for _ in 0..100 {
+                input.push_str("| c |\n");
+            }
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        }
+
+        #[test]
+        fn table_at_100_rows_is_ok() {
+            let mut input = String::from("| h |\n|---|\n");
+            for _ in 0..99 {
+                input.push_str("| c |\n");
+            }
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        }
+
+        #[test]
+        fn table_over_10_columns_is_err() {
+            let header = format!("|{}", " h |".repeat(11));
+            let delimiter = format!("|{}", "---|".repeat(11));
+            let input = format!("{header}\n{delimiter}\n");
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        }
+
+        #[test]
+        fn over_50_blocks_is_err() {
+            let input = "---\n".repeat(51);
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        }
+
+        #[test]
+        fn at_50_blocks_is_ok() {
+            let input = "---\n".repeat(50);
+            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        }
+    }
+
+    mod errors {
+        use crate::{Error, LimitKind, Mrkdwn, Position};
+
+        #[test]
+        fn section_limit_has_position() {
+            let input = format!("# Title\n\nShort.\n\n{}", "a".repeat(3000));
+            let error = Mrkdwn::from(input.as_str()).blockify().unwrap_err();
+            assert_eq!(
+                error,
+                Error::LimitExceeded {
+                    kind: LimitKind::SectionChars,
+                    actual: 3001,
+                    max: 3000,
+                    position: Some(Position { line: 5, column: 1 }),
+                }
+            );
+        }
+
+        #[test]
+        fn header_limit_has_position() {
+            let input = format!("Intro\n\n  ## {}", "a".repeat(151));
+            let error = Mrkdwn::from(input.as_str()).blockify().unwrap_err();
+            assert!(matches!(
+                error,
+                Error::LimitExceeded {
+                    kind: LimitKind::HeaderChars,
+                    actual: 151,
+                    max: 150,
+                    ..
+                }
+            ));
+            assert_eq!(error.position(), Some(Position { line: 3, column: 1 }));
+        }
+
+        #[test]
+        fn block_count_points_at_first_block_over_limit() {
+            let input = "---\n".repeat(51);
+            let error = Mrkdwn::from(input.as_str()).blockify().unwrap_err();
+            assert_eq!(
+                error,
+                Error::LimitExceeded {
+                    kind: LimitKind::Blocks,
+                    actual: 51,
+                    max: 50,
+                    position: Some(Position { line: 51, column: 1 }),
+                }
+            );
+        }
+
+        #[test]
+        fn table_limit_has_position() {
+            let header = format!("|{}", " h |".repeat(11));
+            let delimiter = format!("|{}", "---|".repeat(11));
+            let input = format!("para\n\n{header}\n{delimiter}\n");
+            let error = Mrkdwn::from(input.as_str()).blockify_messages().unwrap_err();
+            assert!(matches!(
+                error,
+                Error::LimitExceeded {
+                    kind: LimitKind::TableCols,
+                    actual: 11,
+                    max: 10,
+                    ..
+                }
+            ));
+            assert_eq!(error.position(), Some(Position { line: 3, column: 1 }));
+        }
+
+        #[test]
+        fn display() {
+            let error = Error::LimitExceeded {
+                kind: LimitKind::SectionChars,
+                actual: 3001,
+                max: 3000,
+                position: Some(Position { line: 5, column: 1 }),
+            };
+            assert_eq!(
+                error.to_string(),
+                "section text has 3001 characters, Slack allows 3000 at line 5, column 1"
+            );
+            assert_eq!(Error::Empty.to_string(), "no input?");
+        }
+    }
+
+    mod validate {
+        use crate::{LimitKind, Limits, Mrkdwn, OverflowPolicy, Position, Span, Violation};
+
+        #[test]
+        fn valid_input_has_no_violations() {
+            assert!(Mrkdwn::from("# Title\n\nBody").validate().unwrap().is_empty());
+        }
+
+        #[test]
+        fn reports_every_violation() {
+            let header = format!("# {}", "a".repeat(151));
+            let section = "b".repeat(3000);
+            let alt_text = format!("![{}](https://example.com/x.png)", "c".repeat(2001));
+            let table = format!("|{}\n|{}\n", " h |".repeat(11), "---|".repeat(11));
+            let dividers = "---\n".repeat(50);
+            let input = format!("{header}\n\n{section}\n\n{alt_text}\n\n{table}\n{dividers}");
+
+            let violations = Mrkdwn::from(input.as_str()).validate().unwrap();
+            let summary: Vec<_> = violations
+                .iter()
+                .map(|v| (v.kind, v.block, v.actual, v.max))
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    (LimitKind::Blocks, 50, 55, 50),
+                    (LimitKind::HeaderChars, 0, 151, 150),
+                    (LimitKind::SectionChars, 2, 3001, 3000),
+                    (LimitKind::AltTextChars, 3, 2001, 2000),
+                    (LimitKind::TableCols, 4, 11, 10),
+                ]
+            );
+        }
+
+        #[test]
+        fn violations_have_spans() {
+            let input = format!("Intro\n\n{}\nmore", "a".repeat(3000));
+            let violations = Mrkdwn::from(input.as_str()).validate().unwrap();
+            assert_eq!(
+                violations,
+                vec![Violation {
+                    kind: LimitKind::SectionChars,
+                    block: 1,
+                    actual: 3006,
+                    max: 3000,
+                    span: Some(Span {
+                        start: Position { line: 3, column: 1 },
+                        end: Position { line: 4, column: 5 },
+                    }),
+                }]
+            );
+            assert_eq!(
+                violations[0].to_string(),
+                "block 1 (line 3, column 1 to line 4, column 5): section text has 3006 \
+                 characters, Slack allows 3000"
+            );
+        }
+
+        #[test]
+        fn applies_overflow_policies_first() {
+            let input = "a".repeat(3000);
+            let limits = Limits::default().with_policy(OverflowPolicy::Truncate);
+            assert!(
+                Mrkdwn::from(input.as_str())
+                    .limits(limits)
+                    .validate()
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
+    mod overflow {
+        use crate::{
+            Block, ColumnSettings, Limit, LimitKind, Limits, Mrkdwn, OverflowPolicy, Truncation,
+        };
+
+        fn truncating(input: &str) -> crate::Conversion {
+            Mrkdwn::from(input)
+                .limits(Limits::default().with_policy(OverflowPolicy::Truncate))
+                .convert()
+                .unwrap()
+        }
+
+        fn splitting(input: &str) -> Vec<Block> {
+            Mrkdwn::from(input)
+                .limits(Limits::default().with_policy(OverflowPolicy::Split))
+                .blockify_messages()
+                .unwrap()
+                .concat()
+        }
+
+        fn cell_text(cell: &serde_json::Value) -> &str {
+            cell["elements"][0]["elements"][0]["text"].as_str().unwrap()
+        }
+
+        #[test]
+        fn truncates_sections() {
+            let conversion = truncating(&"a".repeat(3500));
+            let [Block::Section(text)] = conversion.blocks.as_slice() else { panic!() };
+            assert_eq!(text.chars().count(), 3000);
+            assert!(text.ends_with("a…"));
+            assert_eq!(
+                conversion.truncations,
+                vec![Truncation {
+                    kind: LimitKind::SectionChars,
+                    block: 0,
+                    actual: 3501,
+                    max: 3000
+                }]
+            );
+        }
+
+        #[test]
+        fn truncation_never_cuts_entities() {
+            let conversion = truncating(&"&".repeat(1000));
+            let [Block::Section(text)] = conversion.blocks.as_slice() else { panic!() };
+            assert!(text.chars().count() <= 3000);
+            assert!(text.ends_with("&amp;…"));
+        }
+
+        #[test]
+        fn truncation_never_cuts_links() {
+            let input = format!("{} [Slack](https://slack.com/) tail", "a".repeat(2990));
+            let conversion = truncating(&input);
+            let [Block::Section(text)] = conversion.blocks.as_slice() else { panic!() };
+            assert!(text.ends_with(&format!("{} …", "a".repeat(2990))));
+        }
+
+        #[test]
+        fn truncation_closes_code_fences() {
+            let input = format!("```\n{}\n```", "x".repeat(3500));
+            let conversion = truncating(&input);
+            let [Block::Section(text)] = conversion.blocks.as_slice() else { panic!() };
+            assert!(text.chars().count() <= 3000);
+            assert!(text.ends_with("x…\n```\n"));
+        }
+
+        #[test]
+        fn truncates_headers() {
+            let conversion = truncating(&format!("## {}", "a".repeat(200)));
+            let [Block::Header(text)] = conversion.blocks.as_slice() else { panic!() };
+            assert_eq!(text.chars().count(), 150);
+            assert_eq!(conversion.truncations[0].kind, LimitKind::HeaderChars);
+        }
+
+        #[test]
+        fn splits_headers() {
+            let blocks = splitting(&format!("## {}", "word ".repeat(50)));
+            assert_eq!(blocks.len(), 2);
+            assert!(blocks.iter().all(|block| matches!(block, Block::Header(_))));
+        }
+
+        #[test]
+        fn truncates_alt_text() {
+            let input = format!("![{}](https://example.com/x.png)", "a".repeat(2001));
+            for blocks in [truncating(&input).blocks, splitting(&input)] {
+                let [Block::Image { alt_text, .. }] = blocks.as_slice() else { panic!() };
+                assert_eq!(alt_text.chars().count(), 2000);
+            }
+        }
+
+        #[test]
+        fn truncates_table_rows() {
+            let input = format!("| h | i |\n|---|---|\n{}", "| c | d |\n".repeat(150));
+            let conversion = truncating(&input);
+            let [Block::Table { rows, .. }] = conversion.blocks.as_slice() else { panic!() };
+            assert_eq!(rows.len(), 100);
+            assert_eq!(cell_text(&rows[99][0]), "…");
+            assert_eq!(cell_text(&rows[99][1]), "");
+            assert_eq!(
+                conversion.truncations,
+                vec![Truncation {
+                    kind: LimitKind::TableRows,
+                    block: 0,
+                    actual: 151,
+                    max: 100
+                }]
+            );
+        }
+
+        #[test]
+        fn splits_table_rows_repeating_header() {
+            let input = format!("| h |\n|---|\n{}", "| c |\n".repeat(150));
+            let blocks = splitting(&input);
+            let lengths: Vec<_> = blocks
+                .iter()
+                .map(|block| match block {
+                    Block::Table { rows, .. } => {
+                        assert_eq!(cell_text(&rows[0][0]), "h");
+                        rows.len()
+                    }
+                    block => panic!("unexpected block: {block}"),
+                })
+                .collect();
+            assert_eq!(lengths, vec![100, 52]);
+        }
+
+        #[test]
+        fn table_row_limits_too_small_to_fit_fail() {
+            let input = "| h |\n|---|\n| a |\n| b |\n";
+            for (max, policy) in [(1, OverflowPolicy::Split), (0, OverflowPolicy::Truncate)] {
+                let limits = Limits {
+                    table_rows: Limit { max, policy },
+                    ..Limits::default()
+                };
+                let error = Mrkdwn::from(input).limits(limits).blockify().unwrap_err();
+                assert_eq!(
+                    error.to_string(),
+                    format!("table has 3 rows, Slack allows {max} at line 1, column 1")
+                );
+            }
+        }
+
+        #[test]
+        fn truncates_table_columns() {
+            let header = format!("|{}", " h |".repeat(12));
+            let delimiter = format!("|{}", "--:|".repeat(12));
+            let conversion = truncating(&format!("{header}\n{delimiter}\n"));
+            let [Block::Table { column_settings, rows }] = conversion.blocks.as_slice() else {
+                panic!()
+            };
+            assert_eq!(rows[0].len(), 10);
+            assert_eq!(column_settings.len(), 10);
+            assert_eq!(column_settings[9], ColumnSettings::default());
+            assert_eq!(cell_text(&rows[0][9]), "…");
+        }
+
+        #[test]
+        fn splits_table_columns() {
+            let header = format!("|{}", " h |".repeat(12));
+            let delimiter = format!("|{}", "---|".repeat(12));
+            let blocks = splitting(&format!("{header}\n{delimiter}\n"));
+            let widths: Vec<_> = blocks
+                .iter()
+                .map(|block| match block {
+                    Block::Table { rows, .. } => rows[0].len(),
+                    block => panic!("unexpected block: {block}"),
+                })
+                .collect();
+            assert_eq!(widths, vec![10, 2]);
+        }
+
+        #[test]
+        fn truncates_block_count() {
+            let conversion = truncating(&"---\n".repeat(60));
+            assert_eq!(conversion.blocks.len(), 50);
+            assert_eq!(conversion.blocks[49], Block::Section("…".to_string()));
+            assert_eq!(
+                conversion.truncations,
+                vec![Truncation {
+                    kind: LimitKind::Blocks,
+                    block: 49,
+                    actual: 60,
+                    max: 50
+                }]
+            );
+        }
+
+        #[test]
+        fn split_block_count_is_err_for_a_single_message() {
+            let input = "---\n".repeat(51);
+            let limits = Limits::default().with_policy(OverflowPolicy::Split);
+            assert!(Mrkdwn::from(input.as_str()).limits(limits).blockify().is_err());
+        }
+
+        #[test]
+        fn custom_max_is_enforced() {
+            let mut limits = Limits::default();
+            limits.section_chars.max = 10;
+            assert!(
+                Mrkdwn::from("a".repeat(10).as_str())
+                    .limits(limits)
+                    .blockify()
+                    .is_err()
+            );
+        }
+    }
+
+    mod rich_text {
+        use serde_json::{Value, json};
+
+        use crate::{Block, BlockMode, Markdown, Mrkdwn};
+
+        fn blocks(markdown: &str) -> Vec<Value> {
+            Mrkdwn::from(markdown)
+                .block_mode(BlockMode::RichText)
+                .blockify()
+                .unwrap()
+                .into_iter()
+                .map(Value::from)
+                .collect()
+        }
+
+        fn text(text: &str) -> Value {
+            json!({ "type": "text", "text": text })
+        }
+
+        fn section(elements: Value) -> Value {
+            json!({ "type": "rich_text_section", "elements": elements })
+        }
+
+        #[test]
+        fn paragraph() {
+            assert_eq!(
+                blocks("Some **bold** and `code` with a [link](https://x.com)."),
+                [json!({
+                    "type": "rich_text",
+                    "elements": [section(json!([
+                        text("Some "),
+                        { "type": "text", "text": "bold", "style": { "bold": true } },
+                        text(" and "),
+                        { "type": "text", "text": "code", "style": { "code": true } },
+                        text(" with a "),
+                        { "type": "link", "url": "https://x.com", "text": "link" },
+                        text("."),
+                    ]))],
+                })]
+            );
+        }
+
+        #[test]
+        fn nested_lists() {
+            assert_eq!(
+                blocks("1. First\n   - Nested\n2. Second\n3. Third"),
+                [json!({
+                    "type": "rich_text",
+                    "elements": [
+                        {
+                            "type": "rich_text_list",
+                            "style": "ordered",
+                            "indent": 0,
+                            "elements": [section(json!([text("First")]))],
+                        },
+                        {
+                            "type": "rich_text_list",
+                            "style": "bullet",
+                            "indent": 1,
+                            "elements": [section(json!([text("Nested")]))],
+                        },
+                        {
+                            "type": "rich_text_list",
+                            "style": "ordered",
+                            "indent": 0,
+                            "offset": 1,
+                            "elements": [
+                                section(json!([text("Second")])),
+                                section(json!([text("Third")])),
+                            ],
+                        },
+                    ],
+                })]
+            );
+        }
+
+        #[test]
+        fn task_list() {
+            assert_eq!(
+                blocks("- [ ] Todo\n- [x] Done"),
+                [json!({
+                    "type": "rich_text",
+                    "elements": [{
+                        "type": "rich_text_list",
+                        "style": "bullet",
+                        "indent": 0,
+                        "elements": [
+                            section(json!([text("\u{2610} "), text("Todo")])),
+                            section(json!([text("\u{2611} "), text("Done")])),
+                        ],
+                    }],
+                })]
+            );
+        }
+
+        #[test]
+        fn loose_list_item() {
+            assert_eq!(
+                blocks("- First\n\n  More\n- Second"),
+                [json!({
+                    "type": "rich_text",
+                    "elements": [{
+                        "type": "rich_text_list",
+                        "style": "bullet",
+                        "indent": 0,
+                        "elements": [
+                            section(json!([text("First"), text("\n"), text("More")])),
+                            section(json!([text("Second")])),
+                        ],
+                    }],
+                })]
+            );
+        }
+
+        #[test]
+        fn quote_and_code() {
+            assert_eq!(
+                blocks("> Quoted _text_\n\n```rust\nlet a = 1 < 2;\n```"),
+                [
+                    json!({
+                        "type": "rich_text",
+                        "elements": [{
+                            "type": "rich_text_quote",
+                            "elements": [
+                                text("Quoted "),
+                                { "type": "text", "text": "text", "style": { "italic": true } },
+                            ],
+                        }],
+                    }),
+                    json!({
+                        "type": "rich_text",
+                        "elements": [{
+                            "type": "rich_text_preformatted",
+                            "elements": [text("let a = 1 < 2;")],
+                        }],
+                    }),
+                ]
+            );
+        }
+
+        #[test]
+        fn other_blocks_are_unchanged() {
+            let markdown = "# Title\n\n---\n\n![alt](https://x.com/a.png)\n\n| a |\n| - |\n| 1 |";
+            assert_eq!(
+                Mrkdwn::from(markdown)
+                    .block_mode(BlockMode::RichText)
+                    .blockify()
+                    .unwrap(),
+                Mrkdwn::from(markdown).blockify().unwrap()
+            );
+        }
+
+        #[test]
+        fn minor_heading() {
+            assert_eq!(
+                blocks("### Minor"),
+                [json!({
+                    "type": "rich_text",
+                    "elements": [section(json!([
+                        { "type": "text", "text": "Minor", "style": { "bold": true } },
+                    ]))],
+                })]
+            );
+        }
+
+        #[test]
+        fn round_trips_through_markdown() {
+            let markdown = "Some **bold** text.\n\n1. First\n    - Nested\n2. Second\n\n> Quoted\n\n```\ncode\n```";
+            let blocks = Mrkdwn::from(markdown)
+                .block_mode(BlockMode::RichText)
+                .blockify()
+                .unwrap();
+            assert!(blocks.iter().all(|block| matches!(block, Block::RichText(_))));
+            assert_eq!(Markdown::from_blocks(&blocks).as_str(), markdown);
+        }
+    }
+
+    mod markdown_blocks {
+        use crate::{
+            Block, BlockMode, Error, Limit, LimitKind, Limits, Markdown, Mrkdwn, OverflowPolicy,
+            Position, Truncation,
+        };
+
+        fn blockify(markdown: &str) -> Vec<Block> {
+            Mrkdwn::from(markdown)
+                .block_mode(BlockMode::Markdown)
+                .blockify()
+                .unwrap()
+        }
+
+        fn markdown_limit(max: usize, policy: OverflowPolicy) -> Limits {
+            Limits {
+                markdown_chars: Limit { max, policy },
+                ..Limits::default()
+            }
+        }
+
+        #[test]
+        fn keeps_source() {
+            let markdown = "# Title\n\nSome **bold** and ~~strike~~.\n\n- [ ] Task\n  1. Nested\n\n> Quote\n\n```rust\nlet a = 1;\n```";
+            assert_eq!(blockify(markdown), vec![Block::Markdown(markdown.to_string())]);
+        }
+
+        #[test]
+        fn normalizes_gfm() {
+            let markdown = "Setext\n===\n\n__bold__ and _em_ in a [link][docs], snake_case.<br>Next\n\n* one\n* two\n\nCode:\n\n    indented\n\n<div>html</div>\n\nOn <time datetime=\"2026-10-17\">today</time>.\n\n[docs]: https://x.com/docs";
+            assert_eq!(
+                blockify(markdown),
+                vec![Block::Markdown(
+                    "# Setext\n\n**bold** and *em* in a [link](https://x.com/docs), snake_case.\\\nNext\n\n- one\n- two\n\nCode:\n\n```\nindented\n```\n\nOn 2026-10-17."
+                        .to_string()
+                )]
+            );
+        }
+
+        #[test]
+        fn rewrites_tables_and_images() {
+            let markdown =
+                "Intro\n\n| a |\n| - |\n| 1 |\n\nSee ![alt](https://x.com/a.png) here\n\nEnd";
+            let blocks = blockify(markdown);
+            assert_eq!(blocks[0], Block::Markdown("Intro".to_string()));
+            assert!(matches!(blocks[1], Block::Table { .. }));
+            assert_eq!(blocks[2], Block::Markdown("See".to_string()));
+            assert!(matches!(blocks[3], Block::Image { .. }));
+            assert_eq!(blocks[4], Block::Markdown("here\n\nEnd".to_string()));
+            assert_eq!(blocks.len(), 5);
+        }
+
+        #[test]
+        fn limits_total_markdown() {
+            let markdown =
+                format!("{}\n\n| a |\n| - |\n| 1 |\n\n{}", "a".repeat(6000), "b".repeat(6001));
+            assert_eq!(
+                Mrkdwn::from(markdown.as_str())
+                    .block_mode(BlockMode::Markdown)
+                    .blockify(),
+                Err(Error::LimitExceeded {
+                    kind: LimitKind::MarkdownChars,
+                    actual: 12001,
+                    max: 12000,
+                    position: Some(Position { line: 7, column: 1 }),
+                })
+            );
+
+            let violations = Mrkdwn::from(markdown.as_str())
+                .block_mode(BlockMode::Markdown)
+                .validate()
+                .unwrap();
+            let summary: Vec<_> = violations
+                .iter()
+                .map(|v| (v.kind, v.block, v.actual, v.max))
+                .collect();
+            assert_eq!(summary, vec![(LimitKind::MarkdownChars, 2, 12001, 12000)]);
+        }
+
+        #[test]
+        fn truncates_total_markdown() {
+            let table = "| a |\n| - |\n| 1 |";
+            let markdown = format!("aaaa\n\n{table}\n\nbbbbbbbb\n\n{table}\n\ncccc");
+            let conversion = Mrkdwn::from(markdown.as_str())
+                .block_mode(BlockMode::Markdown)
+                .limits(markdown_limit(10, OverflowPolicy::Truncate))
+                .convert()
+                .unwrap();
+            assert_eq!(conversion.blocks.len(), 3);
+            assert_eq!(conversion.blocks[2], Block::Markdown("bbbbb…".to_string()));
+            assert_eq!(
+                conversion.truncations,
+                vec![Truncation {
+                    kind: LimitKind::MarkdownChars,
+                    block: 2,
+                    actual: 16,
+                    max: 10
+                }]
+            );
+        }
+
+        #[test]
+        fn splits_long_blocks() {
+            let markdown = "aaaa bbbb cccc";
+            let blocks = Mrkdwn::from(markdown)
+                .block_mode(BlockMode::Markdown)
+                .limits(markdown_limit(10, OverflowPolicy::Split))
+                .blockify_messages()
+                .unwrap();
+            assert_eq!(
+                blocks,
+                vec![
+                    vec![Block::Markdown("aaaa bbbb ".to_string())],
+                    vec![Block::Markdown("cccc".to_string())],
+                ]
+            );
+        }
+
+        #[test]
+        fn packs_messages_by_markdown_chars() {
+            let markdown = "# One\n\naaaa\n\n# Two\n\nbbbb\n\n# Three\n\ncccc";
+            let messages = Mrkdwn::from(markdown)
+                .block_mode(BlockMode::Markdown)
+                .limits(markdown_limit(30, OverflowPolicy::Error))
+                .blockify_messages()
+                .unwrap();
+            assert_eq!(
+                messages,
+                vec![
+                    vec![
+                        Block::Markdown("# One\n\naaaa".to_string()),
+                        Block::Markdown("# Two\n\nbbbb".to_string()),
+                    ],
+                    vec![Block::Markdown("# Three\n\ncccc".to_string())],
+                ]
+            );
+        }
+
+        #[test]
+        fn round_trips_block_kit() {
+            let json = r#"{ "blocks": [{ "type": "markdown", "text": "**bold**" }] }"#;
+            assert_eq!(Markdown::from_block_kit(json).unwrap().as_str(), "**bold**");
+        }
+    }
+
+    mod options {
+        use crate::{
+            Block, BlockMode, ConvertOptions, DividerStyle, HeadingStyle, ImageStyle, Mrkdwn,
+        };
+
+        fn blockify(markdown: &str, options: ConvertOptions) -> Vec<Block> {
+            Mrkdwn::from(markdown).options(options).blockify().unwrap()
+        }
+
+        fn mrkdwn(markdown: &str, options: ConvertOptions) -> String {
+            Mrkdwn::from(markdown).options(options).mrkdwnify_raw().unwrap()
+        }
+
+        #[test]
+        fn default_matches_standard_output() {
+            let markdown = "# One\n\n## Two\n\n### Three\n\n- a\n    - b\n\n---\n\n![alt](https://x.com/a.png)";
+            assert_eq!(
+                Mrkdwn::from(markdown).options(ConvertOptions::default()).blockify(),
+                Mrkdwn::from(markdown).blockify()
+            );
+            assert_eq!(ConvertOptions::builder().build(), ConvertOptions::default());
+        }
+
+        #[test]
+        fn headings() {
+            let options = ConvertOptions::builder()
+                .heading(1, HeadingStyle::Header)
+                .heading(2, HeadingStyle::Bold)
+                .heading(3, HeadingStyle::HeaderWithDivider)
+                .build();
+            assert_eq!(
+                blockify("# One\n\n## Two\n\n### Three", options),
+                vec![
+                    Block::Header("One".to_string()),
+                    Block::Section("*Two*".to_string()),
+                    Block::Header("Three".to_string()),
+                    Block::Divider,
+                ]
+            );
+        }
+
+        #[test]
+        fn bold_heading_in_rich_text() {
+            let options = ConvertOptions::builder().heading(2, HeadingStyle::Bold).build();
+            let blocks = Mrkdwn::from("## Two")
+                .block_mode(BlockMode::RichText)
+                .options(options)
+                .blockify()
+                .unwrap();
+            assert!(matches!(blocks[..], [Block::RichText(_)]));
+        }
+
+        #[test]
+        fn list_glyphs_and_indent() {
+            let options = ConvertOptions::builder()
+                .bullets(["-", "+"])
+                .checkboxes("[ ]", "[x]")
+                .indent_width(2)
+                .build();
+            assert_eq!(
+                mrkdwn("- a\n    - b\n        - c", options.clone()),
+                "-   a\n  +   b\n    -   c"
+            );
+            assert_eq!(mrkdwn("- [ ] d\n- [x] e", options), "[ ]   d\n[x]   e");
+
+            let escaped = ConvertOptions::builder()
+                .bullets(["<!channel>"])
+                .checkboxes("&", "<x>")
+                .build();
+            assert_eq!(
+                mrkdwn("- a\n- [ ] b\n- [x] c", escaped.clone()),
+                "&lt;!channel&gt;   a\n&amp;   b\n&lt;x&gt;   c"
+            );
+            assert_eq!(
+                Mrkdwn::from("- a\n- [ ] b").options(escaped).text_fallback().unwrap(),
+                "&lt;!channel&gt; a\n&amp; b"
+            );
+        }
+
+        #[test]
+        fn dividers() {
+            let text = ConvertOptions::builder().divider(DividerStyle::Text("* * *".to_string()));
+            assert_eq!(
+                blockify("a\n\n---\n\nb", text.clone().build()),
+                vec![
+                    Block::Section("a\n".to_string()),
+                    Block::Section("* * *\n".to_string()),
+                    Block::Section("b\n".to_string()),
+                ]
+            );
+            assert_eq!(mrkdwn("a\n\n---\n\nb", text.build()), "a\n\n* * *\nb");
+
+            let escaped = ConvertOptions::builder()
+                .divider(DividerStyle::Text("<-- & -->".to_string()))
+                .build();
+            assert_eq!(
+                blockify("---", escaped.clone()),
+                vec![Block::Section("&lt;-- &amp; --&gt;\n".to_string())]
+            );
+            assert_eq!(mrkdwn("---", escaped), "&lt;-- &amp; --&gt;");
+
+            let omit = ConvertOptions::builder().divider(DividerStyle::Omit).build();
+            assert_eq!(
+                blockify("a\n\n---\n\nb", omit.clone()),
+                vec![Block::Section("a\n".to_string()), Block::Section("b\n".to_string())]
+            );
+            assert_eq!(mrkdwn("a\n\n---\n\nb", omit), "a\nb");
+        }
+
+        #[test]
+        fn images() {
+            let markdown = "See ![a cat](https://x.com/cat.png)";
+            let options = |images| ConvertOptions::builder().images(images).build();
+            assert_eq!(
+                blockify(markdown, options(ImageStyle::Link)),
+                vec![Block::Section("See <https://x.com/cat.png|a cat>\n".to_string())]
+            );
+            assert_eq!(
+                blockify(markdown, options(ImageStyle::AltText)),
+                vec![Block::Section("See a cat\n".to_string())]
+            );
+            assert_eq!(
+                blockify(markdown, options(ImageStyle::Omit)),
+                vec![Block::Section("See \n".to_string())]
+            );
+            assert_eq!(
+                blockify("![a cat](https://x.com/cat.png)", options(ImageStyle::Omit)),
+                vec![]
+            );
+            assert_eq!(mrkdwn(markdown, options(ImageStyle::AltText)), "See a cat");
+        }
+    }
+
+    mod mentions {
+        use serde_json::{Value, json};
+
+        use crate::{Block, BlockMode, Broadcast, Error, Mention, MentionMap, Mrkdwn};
+
+        fn map() -> MentionMap {
+            MentionMap::new()
+                .user("alice", "U123")
+                .channel("deploys", "C123")
+                .usergroup("oncall", "S123")
+                .broadcast(Broadcast::Here)
+        }
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    let map = map();
+                    assert_eq!(
+                        Mrkdwn::from($input).mentions(&map).mrkdwnify_raw().unwrap(),
+                        $expected
+                    );
+                }
+            };
+        }
+
+        test!(user, "Thanks @alice!", "Thanks <@U123>!");
+        test!(channel, "See #deploys.", "See <#C123>.");
+        test!(usergroup, "cc @oncall", "cc <!subteam^S123>");
+        test!(broadcast, "@here the build is red", "<!here> the build is red");
+        test!(unknown_handles, "@bob #random @channel", "@bob #random @channel");
+        test!(inside_word, "team@alice and x#deploys", "team@alice and x#deploys");
+        test!(formatted, "**@alice** and _#deploys_", "*<@U123>* and _<#C123>_");
+        test!(code, "`@alice`", "`@alice`");
+        test!(link_text, "[@alice](https://x.com)", "<https://x.com|@alice>");
+        test!(escaping, "@alice <3 & #deploys", "<@U123> &lt;3 &amp; <#C123>");
+
+        #[test]
+        fn without_resolver() {
+            assert_eq!(Mrkdwn::from("@alice @here").mrkdwnify_raw().unwrap(), "@alice @here");
+        }
+
+        #[test]
+        fn rich_text_elements() {
+            let map = map();
+            let blocks = Mrkdwn::from("Hi @alice in #deploys, **@oncall** @here")
+                .block_mode(BlockMode::RichText)
+                .mentions(&map)
+                .blockify()
+                .unwrap();
+            let [Block::RichText(elements)] = &blocks[..] else { panic!("{blocks:?}") };
+            assert_eq!(
+                elements[0]["elements"],
+                json!([
+                    { "type": "text", "text": "Hi " },
+                    { "type": "user", "user_id": "U123" },
+                    { "type": "text", "text": " in " },
+                    { "type": "channel", "channel_id": "C123" },
+                    { "type": "text", "text": ", " },
+                    { "type": "usergroup", "usergroup_id": "S123", "style": { "bold": true } },
+                    { "type": "text", "text": " " },
+                    { "type": "broadcast", "range": "here" },
+                ])
+            );
+        }
+
+        #[test]
+        fn table_cells() {
+            let map = map();
+            let blocks = Mrkdwn::from("| owner |\n| - |\n| @alice |")
+                .mentions(&map)
+                .blockify()
+                .unwrap();
+            let Value::Object(table) = Value::from(blocks[0].clone()) else { panic!() };
+            assert_eq!(
+                table["rows"][1][0]["elements"][0]["elements"],
+                json!([{ "type": "user", "user_id": "U123" }])
+            );
+        }
+
+        #[test]
+        fn from_json() {
+            let json = r#"{
+                "users": { "alice": "U123" },
+                "channels": { "deploys": "C123" },
+                "usergroups": { "oncall": "S123" },
+                "broadcasts": ["here"]
+            }"#;
+            assert_eq!(MentionMap::from_json(json).unwrap(), map());
+            assert_eq!(MentionMap::from_json("{}").unwrap(), MentionMap::new());
+        }
+
+        #[test]
+        fn from_invalid_json() {
+            for json in ["[]", r#"{ "users": { "alice": 1 } }"#, r#"{ "broadcasts": ["all"] }"#] {
+                assert!(
+                    matches!(MentionMap::from_json(json), Err(Error::InvalidMentionMap { .. })),
+                    "{json}"
+                );
+            }
+        }
+
+        #[test]
+        fn mention_syntax() {
+            assert_eq!(Mention::User("U1".to_string()).to_mrkdwn(), "<@U1>");
+            assert_eq!(Mention::Channel("C1".to_string()).to_mrkdwn(), "<#C1>");
+            assert_eq!(Mention::UserGroup("S1".to_string()).to_mrkdwn(), "<!subteam^S1>");
+            assert_eq!(Mention::Broadcast(Broadcast::Everyone).to_mrkdwn(), "<!everyone>");
+        }
+    }
+
+    mod emoji {
+        use serde_json::{Value, json};
+
+        use crate::{Block, BlockMode, ConvertOptions, EmojiStyle, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).mrkdwnify_raw().unwrap(), $expected);
+                }
+            };
+            ($name:ident, $style:expr, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    let options = ConvertOptions::builder().emoji($style).build();
+                    assert_eq!(
+                        Mrkdwn::from($input).options(options).mrkdwnify_raw().unwrap(),
+                        $expected
+                    );
+                }
+            };
+        }
+
+        test!(shortcode, "Ship it :tada:", "Ship it :tada:");
+        test!(github_name, ":shipit: :man_facepalming:", ":squirrel: :man-facepalming:");
+        test!(times, "at 10:30:00 or 12:45", "at 10:30:00 or 12:45");
+        test!(inside_word, "a:b: and std::fmt", "a:b: and std::fmt");
+        test!(code, "`:shipit:`", "`:shipit:`");
+        test!(keeps_unicode, "Done 🎉", "Done 🎉");
+        test!(to_shortcode, EmojiStyle::Shortcode, "Done 🎉 😄 © ™", "Done :tada: :smile: © ™");
+        test!(to_unicode, EmojiStyle::Unicode, "Done :tada: :+1: :nope:", "Done 🎉 👍 :nope:");
+
+        #[test]
+        fn header() {
+            let options = ConvertOptions::builder().emoji(EmojiStyle::Shortcode).build();
+            let blocks = Mrkdwn::from("# :shipit: Release 🚀")
+                .options(options)
+                .blockify()
+                .unwrap();
+            assert_eq!(blocks[0], Block::Header(":squirrel: Release :rocket:".to_string()));
+        }
+
+        #[test]
+        fn rich_text_elements() {
+            let blocks = Mrkdwn::from("**Done** :shipit:!")
+                .block_mode(BlockMode::RichText)
+                .blockify()
+                .unwrap();
+            let [Block::RichText(elements)] = &blocks[..] else { panic!("{blocks:?}") };
+            assert_eq!(
+                elements[0]["elements"],
+                json!([
+                    { "type": "text", "text": "Done", "style": { "bold": true } },
+                    { "type": "text", "text": " " },
+                    { "type": "emoji", "name": "squirrel" },
+                    { "type": "text", "text": "!" },
+                ])
+            );
+        }
+
+        #[test]
+        fn table_cells() {
+            let blocks = Mrkdwn::from("| status |\n| - |\n| :white_check_mark: |")
+                .blockify()
+                .unwrap();
+            let Value::Object(table) = Value::from(blocks[0].clone()) else { panic!() };
+            assert_eq!(
+                table["rows"][1][0]["elements"][0]["elements"],
+                json!([{ "type": "emoji", "name": "white_check_mark" }])
+            );
+        }
+    }
+
+    mod dates {
+        use serde_json::json;
+
+        use crate::{Block, BlockMode, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).mrkdwnify_raw().unwrap(), $expected);
+                }
+            };
+        }
+
+        test!(
+            utc,
+            r#"Down since <time datetime="2026-10-17T12:00Z">noon</time>."#,
+            "Down since <!date^1792238400^{date_short} {time}|2026-10-17 12:00 UTC>."
+        );
+        test!(
+            offset,
+            r#"<time datetime="2026-10-17T14:00:30.25+02:00">2pm</time>"#,
+            "<!date^1792238430^{date_short} {time}|2026-10-17 12:00 UTC>"
+        );
+        test!(
+            date_only,
+            "<time datetime='2026-10-17'>today</time>",
+            "<!date^1792238400^{date_short}|2026-10-17>"
+        );
+        test!(
+            self_closing,
+            r#"at <time datetime="1970-01-01T00:00:00Z"/> then"#,
+            "at <!date^0^{date_short} {time}|1970-01-01 00:00 UTC> then"
+        );
+        test!(
+            formatted,
+            r#"**<time datetime="2026-10-17T12:00Z">noon</time>**"#,
+            "*<!date^1792238400^{date_short} {time}|2026-10-17 12:00 UTC>*"
+        );
+        test!(
+            invalid,
+            r#"<time datetime="2026-02-30T12:00Z">soon</time> and <time>later</time>"#,
+            "soon and later"
+        );
+        test!(missing_zone, r#"<time datetime="2026-10-17T12:00">noon</time>"#, "noon");
+        test!(
+            non_ascii_zone,
+            r#"at <time datetime="2026-10-17T12:00+1é1">x</time> now"#,
+            "at x now"
+        );
+
+        #[test]
+        fn rich_text_element() {
+            let blocks = Mrkdwn::from(r#"Fixed <time datetime="2026-10-17T12:00Z">noon</time>"#)
+                .block_mode(BlockMode::RichText)
+                .blockify()
+                .unwrap();
+            let [Block::RichText(elements)] = &blocks[..] else { panic!("{blocks:?}") };
+            assert_eq!(
+                elements[0]["elements"],
+                json!([
+                    { "type": "text", "text": "Fixed " },
+                    {
+                        "type": "date",
+                        "timestamp": 1792238400,
+                        "format": "{date_short} {time}",
+                        "fallback": "2026-10-17 12:00 UTC",
+                    },
+                ])
+            );
+        }
+
+        #[test]
+        fn header_fallback() {
+            let blocks = Mrkdwn::from(r#"# Outage <time datetime="2026-10-17T12:00Z">noon</time>"#)
+                .blockify()
+                .unwrap();
+            assert_eq!(blocks[0], Block::Header("Outage 2026-10-17 12:00 UTC".to_string()));
+        }
+    }
+
+    mod references {
+        use serde_json::json;
+
+        use crate::{Block, BlockMode, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).mrkdwnify_raw().unwrap(), $expected);
+                }
+            };
+        }
+
+        test!(
+            full,
+            "See [the docs][1].\n\n[1]: https://x.com/docs",
+            "See <https://x.com/docs|the docs>."
+        );
+        test!(collapsed, "See [Docs][].\n\n[docs]: https://x.com", "See <https://x.com|Docs>.");
+        test!(
+            shortcut,
+            "See [docs].\n\n[DOCS]: <https://x.com> \"Docs\"",
+            "See <https://x.com|docs>."
+        );
+        test!(
+            first_definition_wins,
+            "[a]\n\n[a]: https://one.com\n[a]: https://two.com",
+            "<https://one.com|a>"
+        );
+        test!(formatted, "**[docs]**\n\n[docs]: https://x.com", "*<https://x.com|docs>*");
+        test!(undefined, "[docs] and [x][y]", "[docs] and [x][y]");
+        test!(image, "![logo][l]\n\n[l]: https://x.com/a.png", "<https://x.com/a.png|logo>");
+
+        #[test]
+        fn nested_definition() {
+            let mrkdwn = Mrkdwn::from("[docs]\n\n> [docs]: https://x.com")
+                .mrkdwnify_raw()
+                .unwrap();
+            assert!(mrkdwn.starts_with("<https://x.com|docs>"), "{mrkdwn}");
+        }
+
+        #[test]
+        fn image_block() {
+            let blocks = Mrkdwn::from("![logo][]\n\n[logo]: https://x.com/a.png \"Logo\"")
+                .blockify()
+                .unwrap();
+            assert_eq!(
+                blocks,
+                vec![Block::Image {
+                    url: "https://x.com/a.png".to_string(),
+                    alt_text: "logo".to_string(),
+                    title: Some("Logo".to_string()),
+                }]
+            );
+        }
+
+        #[test]
+        fn section() {
+            let blocks = Mrkdwn::from("[docs][1]\n\n[1]: https://x.com").blockify().unwrap();
+            assert_eq!(blocks, vec![Block::Section("<https://x.com|docs>\n".to_string())]);
+        }
+
+        #[test]
+        fn rich_text_link() {
+            let blocks = Mrkdwn::from("[docs]\n\n[docs]: https://x.com")
+                .block_mode(BlockMode::RichText)
+                .blockify()
+                .unwrap();
+            let [Block::RichText(elements)] = &blocks[..] else { panic!("{blocks:?}") };
+            assert_eq!(
+                elements[0]["elements"],
+                json!([{ "type": "link", "url": "https://x.com", "text": "docs" }])
+            );
+        }
+    }
+
+    mod footnotes {
+        use serde_json::json;
+
+        use crate::{
+            Block, BlockMode, ConvertOptions, Diagnostic, DividerStyle, Error, FootnoteStyle,
+            Limit, LimitKind, Limits, Markdown, Mrkdwn, OverflowPolicy, Position,
+        };
+
+        /// Text referencing `count` footnotes whose definitions are `len` characters long.
+        fn long_footnotes(count: usize, len: usize) -> String {
+            let references: String = (0..count).map(|i| format!("[^{i}]")).collect();
+            let definitions: String =
+                (0..count).map(|i| format!("[^{i}]: {}\n", "x".repeat(len))).collect();
+            format!("Text{references}.\n\n{definitions}")
+        }
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).mrkdwnify_raw().unwrap(), $expected);
+                }
+            };
+        }
+
+        test!(
+            numbered_by_first_use,
+            "A[^b] B[^a] C[^b].\n\n[^a]: First.\n[^b]: Second.",
+            "A¹ B² C¹.\n\n----------\n¹ Second.\n² First."
+        );
+        test!(
+            definitions_in_place,
+            "[^n]: Defined first.\n\nText[^n].\n\nMore.",
+            "Text¹.\nMore.\n\n----------\n¹ Defined first."
+        );
+        test!(unreferenced, "Text.\n\n[^n]: Unused.", "Text.");
+        test!(
+            formatted_definition,
+            "Text[^n].\n\n[^n]: See **[docs](https://x.com)**.",
+            "Text¹.\n\n----------\n¹ See *<https://x.com|docs>*."
+        );
+        test!(
+            nested,
+            "Text[^a].\n\n[^a]: See[^b].\n[^b]: Deeper.",
+            "Text¹.\n\n----------\n¹ See².\n² Deeper."
+        );
+        test!(
+            many,
+            "1[^1] 2[^2] 3[^3] 4[^4] 5[^5] 6[^6] 7[^7] 8[^8] 9[^9] 10[^10]\n\n[^1]: a\n[^2]: b\n[^3]: c\n[^4]: d\n[^5]: e\n[^6]: f\n[^7]: g\n[^8]: h\n[^9]: i\n[^10]: j",
+            "1¹ 2² 3³ 4⁴ 5⁵ 6⁶ 7⁷ 8⁸ 9⁹ 10¹⁰\n\n----------\n¹ a\n² b\n³ c\n⁴ d\n⁵ e\n⁶ f\n⁷ g\n⁸ h\n⁹ i\n¹⁰ j"
+        );
+        test!(undefined, "Text[^missing].", "Text[^missing].");
+
+        #[test]
+        fn bracket_style() {
+            let options = ConvertOptions::builder().footnotes(FootnoteStyle::Bracket).build();
+            assert_eq!(
+                Mrkdwn::from("Text[^n].\n\n[^n]: Body.")
+                    .options(options)
+                    .mrkdwnify_raw()
+                    .unwrap(),
+                "Text[1].\n\n----------\n[1] Body."
+            );
+        }
+
+        #[test]
+        fn divider_style() {
+            let mrkdwn = |divider| {
+                let options = ConvertOptions::builder().divider(divider).build();
+                Mrkdwn::from("Text[^n].\n\n[^n]: Body.")
+                    .options(options)
+                    .mrkdwnify_raw()
+                    .unwrap()
+            };
+            assert_eq!(mrkdwn(DividerStyle::Text("* * *".to_string())), "Text¹.\n\n* * *\n¹ Body.");
+            assert_eq!(mrkdwn(DividerStyle::Omit), "Text¹.\n\n¹ Body.");
+        }
+
+        #[test]
+        fn context_block() {
+            let blocks = Mrkdwn::from("# Title[^a]\n\nText[^b].\n\n[^a]: One.\n[^b]: Two & more.")
+                .blockify()
+                .unwrap();
+            assert_eq!(
+                blocks,
+                vec![
+                    Block::Header("Title¹".to_string()),
+                    Block::Divider,
+                    Block::Section("Text².\n".to_string()),
+                    Block::Context(vec!["¹ One.\n² Two &amp; more.".to_string()]),
+                ]
+            );
+            assert_eq!(
+                serde_json::Value::from(blocks[3].clone()),
+                json!({
+                    "type": "context",
+                    "elements": [{ "type": "mrkdwn", "text": "¹ One.\n² Two &amp; more." }],
+                })
+            );
+        }
+
+        #[test]
+        fn long_context_fails_by_default() {
+            let input = long_footnotes(5, 700);
+            let error = Mrkdwn::from(input.as_str()).blockify().unwrap_err();
+            assert!(
+                matches!(error, Error::LimitExceeded { kind: LimitKind::ContextChars, .. }),
+                "{error}"
+            );
+            assert!(error.to_string().starts_with("context text has "), "{error}");
+        }
+
+        #[test]
+        fn long_context_follows_policy() {
+            let input = long_footnotes(5, 700);
+            let limits = Limits::default().with_policy(OverflowPolicy::Split);
+            let blocks = Mrkdwn::from(input.as_str()).limits(limits).blockify().unwrap();
+            let [_, Block::Context(texts)] = &blocks[..] else { panic!("{blocks:?}") };
+            assert_eq!(texts.len(), 2);
+            assert!(texts.iter().all(|text| text.chars().count() <= 3000));
+            assert!(texts.iter().all(|text| text.lines().all(|line| line.len() > 700)));
+
+            let limits = Limits::default().with_policy(OverflowPolicy::Truncate);
+            let conversion = Mrkdwn::from(input.as_str()).limits(limits).convert().unwrap();
+            let [_, Block::Context(texts)] = &conversion.blocks[..] else { panic!() };
+            assert_eq!(texts.len(), 1);
+            assert!(texts[0].ends_with('…'));
+            assert_eq!(conversion.truncations[0].kind, LimitKind::ContextChars);
+        }
+
+        #[test]
+        fn context_elements_split_into_blocks() {
+            let input = long_footnotes(3, 10);
+            let limits = Limits {
+                context_chars: Limit { max: 20, policy: OverflowPolicy::Split },
+                context_elements: Limit { max: 2, policy: OverflowPolicy::Split },
+                ..Limits::default()
+            };
+            let blocks = Mrkdwn::from(input.as_str()).limits(limits).blockify().unwrap();
+            let [_, Block::Context(first), Block::Context(second)] = &blocks[..] else {
+                panic!("{blocks:?}")
+            };
+            assert_eq!((first.len(), second.len()), (2, 1));
+
+            let limits = Limits {
+                context_elements: Limit { max: 2, policy: OverflowPolicy::Error },
+                ..limits
+            };
+            let violations = Mrkdwn::from(input.as_str()).limits(limits).validate().unwrap();
+            let summary: Vec<_> = violations.iter().map(|v| (v.kind, v.actual)).collect();
+            assert_eq!(summary, vec![(LimitKind::ContextElements, 3)]);
+        }
+
+        #[test]
+        fn rich_text() {
+            let blocks = Mrkdwn::from("Text[^n].\n\n[^n]: Body.")
+                .block_mode(BlockMode::RichText)
+                .blockify()
+                .unwrap();
+            let [Block::RichText(elements), Block::Context(texts)] = &blocks[..] else {
+                panic!("{blocks:?}")
+            };
+            assert_eq!(
+                elements[0]["elements"],
+                json!([
+                    { "type": "text", "text": "Text" },
+                    { "type": "text", "text": "¹" },
+                    { "type": "text", "text": "." },
+                ])
+            );
+            assert_eq!(texts, &vec!["¹ Body.".to_string()]);
+        }
+
+        #[test]
+        fn markdown_blocks_keep_source() {
+            let input = "Text[^n].\n\n[^n]: Body.";
+            let blocks = Mrkdwn::from(input)
+                .block_mode(BlockMode::Markdown)
+                .blockify()
+                .unwrap();
+            assert_eq!(blocks, vec![Block::Markdown(input.to_string())]);
+        }
+
+        #[test]
+        fn last_message() {
+            let input = format!("Text[^n].\n\n{}[^n]: Body.", "# Part\n\n".repeat(30));
+            let messages = Mrkdwn::from(input.as_str()).blockify_messages().unwrap();
+            assert_eq!(messages.len(), 2);
+            assert_eq!(messages[1].last(), Some(&Block::Context(vec!["¹ Body.".to_string()])));
+        }
+
+        #[test]
+        fn diagnostics() {
+            let input = "Fine[^a].\n\n> Broken[^b] and `[^code]`.\n\n[^a]: A[^c].";
+            let expected = vec![
+                Diagnostic::UndefinedFootnote {
+                    label: "b".to_string(),
+                    position: Some(Position { line: 3, column: 3 }),
+                },
+                Diagnostic::UndefinedFootnote {
+                    label: "c".to_string(),
+                    position: Some(Position { line: 5, column: 7 }),
+                },
+            ];
+            assert_eq!(Mrkdwn::from(input).diagnostics().unwrap(), expected);
+            assert_eq!(Mrkdwn::from(input).convert().unwrap().diagnostics, expected);
+            assert_eq!(expected[0].to_string(), "footnote [^b] is not defined at line 3, column 3");
+        }
+
+        #[test]
+        fn from_context_block() {
+            let json = r#"[{ "type": "context", "elements": [
+                { "type": "image", "image_url": "https://x.com/a.png", "alt_text": "a" },
+                { "type": "mrkdwn", "text": "*Note*" },
+                { "type": "plain_text", "text": "1 < 2" }
+            ] }]"#;
+            assert_eq!(Markdown::from_block_kit(json).unwrap().as_str(), "**Note**\n1 \\< 2");
+        }
+    }
+
+    mod blockquotes {
+        use crate::{Block, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).mrkdwnify_raw().unwrap(), $expected);
+                }
+            };
+        }
+
+        test!(single_line, "> quoted", "> quoted");
+        test!(soft_break, "> line 1\nline 2", "> line 1\n> line 2");
+        test!(hard_break, "> line 1  \n> line 2", "> line 1\n> line 2");
+        test!(paragraphs, "> one\n>\n> two", "> one\n> two");
+        test!(list, "> - one\n> - two\n>\n> after", "> •   one\n> •   two\n>\n> after");
+        test!(nested_list, "> 1. one\n>    - inner", "> 1.  one\n>     •   inner");
+        test!(code, "> ```\n> let a = 1;\n>   b\n> ```", "> ```\n> let a = 1;\n>   b\n> ```");
+        test!(
+            nested,
+            "> outer\n>\n> > inner\n> > more\n>\n> back",
+            "> outer\n> > inner\n> > more\n> back"
+        );
+        test!(after_quote, "> quoted\n\nplain", "> quoted\nplain");
+
+        #[test]
+        fn section() {
+            let blocks = Mrkdwn::from("> *Re:* the review\n>\n> > Original\n> > comment")
+                .blockify()
+                .unwrap();
+            assert_eq!(
+                blocks,
+                vec![Block::Section("> _Re:_ the review\n> > Original\n> > comment\n".to_string())]
+            );
+        }
+
+        #[test]
+        fn split_section() {
+            let line = "word ".repeat(100);
+            let input = format!("> {}", [line.trim_end(); 8].join("\n>\n> "));
+            let blocks = Mrkdwn::from(input.as_str())
+                .split_long_sections(true)
+                .blockify()
+                .unwrap();
+            assert!(blocks.len() > 1, "{blocks:?}");
+            for block in blocks {
+                let Block::Section(text) = block else { panic!("{block:?}") };
+                assert!(text.lines().all(|line| line.starts_with('>')), "{text}");
+            }
+        }
+    }
+
+    mod lists {
+        use crate::{Block, ConvertOptions, Mrkdwn, NumberStyle};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).mrkdwnify_raw().unwrap(), $expected);
+                }
+            };
+        }
+
+        test!(start_number, "3. Third\n4. Fourth", "3.  Third\n4.  Fourth");
+        test!(zero_start, "0. Zero\n1. One", "0.  Zero\n1.  One");
+        test!(tight, "- a\n- b\n\nAfter", "•   a\n•   b\n\nAfter");
+        test!(loose, "- a\n\n- b\n- c", "•   a\n\n•   b\n\n•   c");
+        test!(nested_then_item, "- a\n  - b\n- c", "•   a\n    •   b\n•   c");
+        test!(
+            continuation_paragraph,
+            "1. First\n\n   More about it\n   over two lines.\n2. Second",
+            "1.  First\n\n    More about it\n    over two lines.\n\n2.  Second"
+        );
+        test!(
+            code_block,
+            "- Run:\n  ```\n  cargo test\n\n  cargo build\n  ```\n- Done",
+            "•   Run:\n    ```\n    cargo test\n\n    cargo build\n    ```\n•   Done"
+        );
+        test!(
+            quote,
+            "- Review:\n  > Looks good\n  > to me",
+            "•   Review:\n    > Looks good\n    > to me"
+        );
+        test!(nested_after_paragraphs, "- a\n\n  text\n\n  - b", "•   a\n\n    text\n\n    •   b");
+        test!(task_list, "- [ ] a\n- [x] b", "\u{2610}   a\n\u{2611}   b");
+
+        #[test]
+        fn nested_numbers() {
+            let options = ConvertOptions::builder().numbers(NumberStyle::Nested).build();
+            let input = "1. a\n   1. b\n      1. c\n         1. d\n      2. e\n      3. f\n      4. g\n   2. h";
+            assert_eq!(
+                Mrkdwn::from(input).options(options).mrkdwnify_raw().unwrap(),
+                "1.  a\n    a.  b\n        i.  c\n            1.  d\n        ii.  e\n        iii.  f\n        iv.  g\n    b.  h"
+            );
+        }
+
+        #[test]
+        fn nested_numbers_under_bullets() {
+            let options = ConvertOptions::builder().numbers(NumberStyle::Nested).build();
+            let blocks = Mrkdwn::from("- a\n  1. b\n  2. c")
+                .options(options)
+                .blockify()
+                .unwrap();
+            assert_eq!(blocks, vec![Block::Section("•   a\n    a.  b\n    b.  c\n\n".to_string())]);
+        }
+    }
+
+    mod text_tables {
+        use crate::{Block, ConvertOptions, Mrkdwn, TextTableStyle};
+
+        fn monospace(input: &str) -> String {
+            let options = ConvertOptions::builder()
+                .text_tables(TextTableStyle::Monospace)
+                .build();
+            Mrkdwn::from(input).options(options).mrkdwnify_raw().unwrap()
+        }
+
+        #[test]
+        fn aligned() {
+            assert_eq!(
+                monospace(
+                    "| Name | Score | Note |\n|:-|-:|:-:|\n| Alice | 10 | ok |\n| Bob | 7 | late |"
+                ),
+                "```\nName  | Score | Note\n------|-------|-----\nAlice |    10 |  ok\nBob   |     7 | late\n```"
+            );
+        }
+
+        #[test]
+        fn wide_characters() {
+            assert_eq!(
+                monospace("| 名前 | x |\n|-|-|\n| 山田太郎 | 1 |\n| Bob | 2 |"),
+                "```\n名前     | x\n---------|--\n山田太郎 | 1\nBob      | 2\n```"
+            );
+        }
+
+        #[test]
+        fn formatting_stripped() {
+            assert_eq!(
+                monospace("| **a** | [b](https://x.com) |\n|-|-|\n| `c` | ~~d~~ < e |"),
+                "```\na | b\n--|------\nc | d &lt; e\n```"
+            );
+        }
+
+        #[test]
+        fn short_rows() {
+            assert_eq!(monospace("| a | b |\n|-|-|\n| 1 |"), "```\na | b\n--|--\n1 |\n```");
+        }
+
+        #[test]
+        fn default_pipes() {
+            assert_eq!(
+                Mrkdwn::from("| a | b |\n|-|-|\n| *1* | 2 |").mrkdwnify_raw().unwrap(),
+                "| a | b |\n| _1_ | 2 |"
+            );
+        }
+
+        #[test]
+        fn in_list() {
+            let options = ConvertOptions::builder()
+                .text_tables(TextTableStyle::Monospace)
+                .build();
+            let blocks = Mrkdwn::from("- Results:\n\n  | a | bb |\n  |-|-|\n  | 1 | 2 |")
+                .options(options)
+                .blockify()
+                .unwrap();
+            assert_eq!(
+                blocks,
+                vec![Block::Section(
+                    "•   Results:\n\n    ```\n    a | bb\n    --|---\n    1 | 2\n    ```\n\n"
+                        .to_string()
+                )]
+            );
+        }
+
+        #[test]
+        fn top_level_blocks_unchanged() {
+            let options = ConvertOptions::builder()
+                .text_tables(TextTableStyle::Monospace)
+                .build();
+            let blocks = Mrkdwn::from("| a |\n|-|\n| 1 |").options(options).blockify().unwrap();
+            assert!(matches!(blocks[..], [Block::Table { .. }]), "{blocks:?}");
+        }
+    }
+
+    mod table_fallbacks {
+        use crate::{
+            Block, ConvertOptions, Error, Limit, LimitKind, Limits, Mrkdwn, OverflowPolicy,
+            TableFallback,
+        };
+
+        fn blockify(input: &str, fallback: TableFallback) -> Vec<Block> {
+            let options = ConvertOptions::builder().table_fallback(fallback).build();
+            Mrkdwn::from(input)
+                .options(options)
+                .split_long_sections(true)
+                .blockify()
+                .unwrap()
+        }
+
+        fn long_table() -> String {
+            format!("| Name | Score |\n|---|--:|\n{}", "| Alice | 10 |\n".repeat(150))
+        }
+
+        fn wide_table() -> String {
+            let header = format!("|{}", " h |".repeat(12));
+            let delimiter = format!("|{}", "---|".repeat(12));
+            let row = format!("|{}", " c |".repeat(12));
+            format!("{header}\n{delimiter}\n{row}\n")
+        }
+
+        /// Converts `input` into messages, splitting them at tables, and returns the rows of each
+        /// message's table.
+        fn split_messages(input: &str, fallback: TableFallback) -> Vec<usize> {
+            let options = ConvertOptions::builder().table_fallback(fallback).build();
+            let limits = Limits {
+                tables: Limit { max: 1, policy: OverflowPolicy::Split },
+                ..Limits::default()
+            };
+            Mrkdwn::from(input)
+                .options(options)
+                .limits(limits)
+                .blockify_messages()
+                .unwrap()
+                .iter()
+                .map(|message| {
+                    let [block] = message.as_slice() else { panic!("{message:?}") };
+                    rows(block)
+                })
+                .collect()
+        }
+
+        fn rows(block: &Block) -> usize {
+            let Block::Table { rows, .. } = block else { panic!("unexpected block: {block}") };
+            rows.len()
+        }
+
+        #[test]
+        fn off_by_default() {
+            let error = Mrkdwn::from(long_table().as_str()).blockify().unwrap_err();
+            assert!(matches!(error, Error::LimitExceeded { kind: LimitKind::TableRows, .. }));
+        }
+
+        #[test]
+        fn small_tables_unchanged() {
+            for fallback in [TableFallback::Auto, TableFallback::Code, TableFallback::Records] {
+                let blocks = blockify("| a |\n|-|\n| 1 |", fallback);
+                assert!(matches!(blocks[..], [Block::Table { .. }]), "{blocks:?}");
+            }
+        }
+
+        #[test]
+        fn split_rows() {
+            assert_eq!(split_messages(&long_table(), TableFallback::SplitRows), vec![100, 52]);
+        }
+
+        #[test]
+        fn code() {
+            let blocks = blockify(&wide_table(), TableFallback::Code);
+            let [Block::Section(text)] = blocks.as_slice() else { panic!("{blocks:?}") };
+            assert!(text.starts_with("```\nh | h | h"), "{text}");
+            assert!(text.contains("\nc | c | c"), "{text}");
+        }
+
+        #[test]
+        fn records() {
+            let input =
+                "| Name | Score | Note |\n|-|-|-|\n| Alice | **10** | |\n| Bob | 7 | late |";
+            let options = ConvertOptions::builder()
+                .table_fallback(TableFallback::Records)
+                .build();
+            let mut limits = crate::Limits::default();
+            limits.table_cols.max = 2;
+            let blocks = Mrkdwn::from(input)
+                .options(options)
+                .limits(limits)
+                .blockify()
+                .unwrap();
+            assert_eq!(
+                blocks,
+                vec![Block::Section(
+                    "*Name:* Alice, *Score:* *10*\n*Name:* Bob, *Score:* 7, *Note:* late\n"
+                        .to_string()
+                )]
+            );
+        }
+
+        #[test]
+        fn records_header_only() {
+            let table = wide_table();
+            let header: Vec<_> = table.lines().take(2).collect();
+            let blocks = blockify(&header.join("\n"), TableFallback::Records);
+            assert_eq!(blocks, vec![Block::Section(format!("{}\n", ["*h*"; 12].join(", ")))]);
+        }
+
+        #[test]
+        fn auto_splits_long_tables_across_messages() {
+            assert_eq!(split_messages(&long_table(), TableFallback::Auto), vec![100, 52]);
+        }
+
+        #[test]
+        fn auto_draws_long_tables_as_code_in_one_message() {
+            let blocks = blockify(&long_table(), TableFallback::Auto);
+            let [Block::Section(text)] = blocks.as_slice() else { panic!("{blocks:?}") };
+            assert!(text.starts_with("```\nName  | Score\n"), "{text}");
+        }
+
+        #[test]
+        fn auto_draws_wide_tables_as_code() {
+            let blocks = blockify(&wide_table(), TableFallback::Auto);
+            assert!(matches!(&blocks[..], [Block::Section(text)] if text.starts_with("```")));
+        }
+
+        #[test]
+        fn auto_lists_records_too_long_for_code() {
+            let row = format!("|{}", " cell |".repeat(12));
+            let input = format!("{}{}", wide_table(), format!("{row}\n").repeat(300));
+            let blocks = blockify(&input, TableFallback::Auto);
+            let [Block::Section(text), ..] = blocks.as_slice() else { panic!("{blocks:?}") };
+            assert!(text.starts_with("*h:* c, *h:* c"), "{text}");
+        }
+
+        #[test]
+        fn markdown_mode() {
+            let blocks = Mrkdwn::from(wide_table().as_str())
+                .block_mode(crate::BlockMode::Markdown)
+                .options(ConvertOptions::builder().table_fallback(TableFallback::Code).build())
+                .blockify()
+                .unwrap();
+            assert!(matches!(&blocks[..], [Block::Section(text)] if text.starts_with("```")));
+        }
+    }
+
+    mod tables_per_message {
+        use crate::{Block, BlockMode, Limit, LimitKind, Limits, Mrkdwn, OverflowPolicy};
+
+        const TWO_TABLES: &str = "| a |\n|-|\n| 1 |\n\nBetween.\n\n| b |\n|-|\n| 2 |";
+
+        fn limits(policy: OverflowPolicy) -> Limits {
+            Limits {
+                tables: Limit { max: 1, policy },
+                ..Limits::default()
+            }
+        }
+
+        #[test]
+        fn fails_by_default() {
+            let error = Mrkdwn::from(TWO_TABLES).blockify().unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "message has 2 table blocks, Slack allows 1 at line 7, column 1"
+            );
+        }
+
+        #[test]
+        fn validate_reports_second_table() {
+            let violations = Mrkdwn::from(TWO_TABLES).validate().unwrap();
+            let summary: Vec<_> = violations
+                .iter()
+                .map(|violation| (violation.kind, violation.block, violation.actual))
+                .collect();
+            assert_eq!(summary, vec![(LimitKind::Tables, 2, 2)]);
+        }
+
+        #[test]
+        fn truncate_renders_rest_as_text() {
+            let blocks = Mrkdwn::from(TWO_TABLES)
+                .limits(limits(OverflowPolicy::Truncate))
+                .blockify()
+                .unwrap();
+            assert!(matches!(blocks[0], Block::Table { .. }), "{blocks:?}");
+            assert_eq!(blocks[2], Block::Section("| b |\n| 2 |\n".to_string()));
+        }
+
+        #[test]
+        fn truncate_counts_split_tables() {
+            let rows: String = (0..150).map(|i| format!("| {i} |\n")).collect();
+            let input = format!("| a |\n|-|\n{rows}\n| b |\n|-|\n| 2 |");
+            let limits = Limits {
+                table_rows: Limit { max: 100, policy: OverflowPolicy::Split },
+                ..limits(OverflowPolicy::Truncate)
+            };
+            let blocks = Mrkdwn::from(input.as_str()).limits(limits).blockify().unwrap();
+            assert!(matches!(blocks[0], Block::Section(_)), "{blocks:?}");
+            assert!(matches!(blocks[1..], [Block::Table { .. }]), "{blocks:?}");
+        }
+
+        #[test]
+        fn truncate_in_markdown_mode() {
+            let blocks = Mrkdwn::from(TWO_TABLES)
+                .block_mode(BlockMode::Markdown)
+                .limits(limits(OverflowPolicy::Truncate))
+                .blockify()
+                .unwrap();
+            assert!(matches!(blocks[0], Block::Table { .. }), "{blocks:?}");
+            assert_eq!(blocks[1], Block::Markdown("Between.".to_string()));
+            assert_eq!(blocks[2], Block::Section("| b |\n| 2 |\n".to_string()));
+        }
+
+        #[test]
+        fn split_fails_in_one_message() {
+            let result = Mrkdwn::from(TWO_TABLES)
+                .limits(limits(OverflowPolicy::Split))
+                .blockify();
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn messages_split_at_tables() {
+            let messages = Mrkdwn::from(TWO_TABLES).blockify_messages().unwrap();
+            assert_eq!(messages.len(), 2);
+            assert!(matches!(messages[0][..], [Block::Table { .. }, Block::Section(_)]));
+            assert!(matches!(messages[1][..], [Block::Table { .. }]));
+        }
+
+        #[test]
+        fn higher_limit() {
+            let limits = Limits {
+                tables: Limit { max: 2, policy: OverflowPolicy::Error },
+                ..Limits::default()
+            };
+            assert_eq!(Mrkdwn::from(TWO_TABLES).limits(limits).blockify().unwrap().len(), 3);
+        }
+    }
+
+    mod table_blocks {
+        use serde_json::{Value, json};
+
+        use crate::{Block, ColumnSettings, ColumnWrap, ConvertOptions, Mrkdwn, TableCells};
+
+        const TABLE: &str = "| Name | Score |\n|:-|-:|\n| **Alice** | 10 |";
+
+        fn table(input: &str, options: ConvertOptions) -> Value {
+            let blocks = Mrkdwn::from(input).options(options).blockify().unwrap();
+            let [block] = blocks.as_slice() else { panic!("{blocks:?}") };
+            Value::from(block.clone())
+        }
+
+        fn cell(input: &str) -> Value {
+            table(&format!("| h |\n|-|\n| {input} |"), ConvertOptions::default())["rows"][1][0]
+                ["elements"][0]["elements"]
+                .clone()
+        }
+
+        #[test]
+        fn wraps_columns() {
+            let options = ConvertOptions::builder()
+                .column_wrap(ColumnWrap::Columns(vec![0]))
+                .build();
+            assert_eq!(
+                table(TABLE, options)["column_settings"],
+                json!([{ "align": "left", "is_wrapped": true }, { "align": "right" }])
+            );
+
+            let options = ConvertOptions::builder().column_wrap(ColumnWrap::All).build();
+            let blocks = Mrkdwn::from("| a | b |\n|-|-|").options(options).blockify().unwrap();
+            let [Block::Table { column_settings, .. }] = blocks.as_slice() else { panic!() };
+            let wrapped = ColumnSettings { align: None, is_wrapped: true };
+            assert_eq!(column_settings, &vec![wrapped.clone(), wrapped]);
+        }
+
+        #[test]
+        fn default_settings_omitted() {
+            let value = table("| a | b |\n|-|-|", ConvertOptions::default());
+            assert_eq!(value.get("column_settings"), None);
+        }
+
+        #[test]
+        fn bold_header() {
+            let options = ConvertOptions::builder().bold_table_header(true).build();
+            let value = table(TABLE, options);
+            assert_eq!(
+                value["rows"][0][0]["elements"][0]["elements"],
+                json!([{ "type": "text", "text": "Name", "style": { "bold": true } }])
+            );
+            assert_eq!(
+                value["rows"][1][1]["elements"][0]["elements"],
+                json!([{ "type": "text", "text": "10" }])
+            );
+        }
+
+        #[test]
+        fn raw_text_cells() {
+            let options = ConvertOptions::builder().table_cells(TableCells::RawText).build();
+            assert_eq!(
+                table(TABLE, options)["rows"],
+                json!([
+                    [{ "type": "raw_text", "text": "Name" }, { "type": "raw_text", "text": "Score" }],
+                    [{ "type": "raw_text", "text": "Alice" }, { "type": "raw_text", "text": "10" }],
+                ])
+            );
+        }
+
+        #[test]
+        fn raw_text_with_bold_header() {
+            let options = ConvertOptions::builder()
+                .table_cells(TableCells::RawText)
+                .bold_table_header(true)
+                .build();
+            let rows = table(TABLE, options)["rows"].clone();
+            assert_eq!(rows[0][0]["type"], "rich_text");
+            assert_eq!(rows[1][0], json!({ "type": "raw_text", "text": "Alice" }));
+        }
+
+        #[test]
+        fn raw_text_flattens_images_and_breaks() {
+            let options = ConvertOptions::builder().table_cells(TableCells::RawText).build();
+            let value = table("| h |\n|-|\n| a<br>b ![logo](https://x.com/a.png) |", options);
+            assert_eq!(value["rows"][1][0], json!({ "type": "raw_text", "text": "a\nb logo" }));
+        }
+
+        #[test]
+        fn line_breaks() {
+            assert_eq!(
+                cell("a<br/>b<BR >c"),
+                json!([
+                    { "type": "text", "text": "a" },
+                    { "type": "text", "text": "\n" },
+                    { "type": "text", "text": "b" },
+                    { "type": "text", "text": "\n" },
+                    { "type": "text", "text": "c" },
+                ])
+            );
+        }
+
+        #[test]
+        fn images_as_links() {
+            assert_eq!(
+                cell("![logo](https://x.com/a.png)"),
+                json!([{ "type": "link", "url": "https://x.com/a.png", "text": "logo" }])
+            );
+        }
+
+        #[test]
+        fn emoji() {
+            assert_eq!(cell(":tada:"), json!([{ "type": "emoji", "name": "tada" }]));
+        }
+
+        #[test]
+        fn footnotes_in_markdown_mode() {
+            let blocks = Mrkdwn::from("| h |\n|-|\n| a[^1] |\n\n[^1]: Note.")
+                .block_mode(crate::BlockMode::Markdown)
+                .blockify()
+                .unwrap();
+            let Block::Table { rows, .. } = &blocks[0] else { panic!("{blocks:?}") };
+            assert_eq!(
+                rows[1][0]["elements"][0]["elements"],
+                json!([{ "type": "text", "text": "a" }, { "type": "text", "text": "[^1]" }])
+            );
+        }
+
+        #[test]
+        fn round_trip() {
+            let block = Block::Table {
+                column_settings: vec![
+                    ColumnSettings::default(),
+                    ColumnSettings { align: Some("right".to_string()), is_wrapped: true },
+                ],
+                rows: vec![vec![json!({ "type": "raw_text", "text": "a" })]],
+            };
+            assert_eq!(Block::try_from(Value::from(block.clone())).unwrap(), block);
+        }
+    }
+
+    mod mrkdwnify_raw {
+        use crate::Mrkdwn;
+
+        const INPUT: &str = "Say \"hi\"\tto `C:\\temp` & 1 < 2\n\n---\n\nBye";
+
+        #[test]
+        fn raw() {
+            assert_eq!(
+                Mrkdwn::from(INPUT).mrkdwnify_raw().unwrap(),
+                "Say \"hi\"\tto `C:\\temp` &amp; 1 &lt; 2\n\n----------\nBye"
+            );
         }
 
         #[test]
-        fn header_over_150_chars_is_err() {
-            let input = format!("# {}", "a".repeat(151));
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        fn json() {
+            let json = Mrkdwn::from(INPUT).mrkdwnify_json().unwrap();
+            assert_eq!(json, r#""Say \"hi\"\tto `C:\\temp` &amp; 1 &lt; 2\n\n----------\nBye""#);
+            let parsed: String = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, Mrkdwn::from(INPUT).mrkdwnify_raw().unwrap());
         }
 
         #[test]
-        fn section_at_3000_chars_is_ok() {
-            // A 2999-char paragraph renders as 3000 chars (trailing newline included).
-            let input = "a".repeat(2999);
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        #[allow(deprecated)]
+        fn legacy_escaping_unchanged() {
+            assert_eq!(
+                Mrkdwn::from(INPUT).mrkdwnify().unwrap(),
+                "Say \\\"hi\\\"\tto `C:\\temp` &amp; 1 &lt; 2\\n\\n----------\\nBye"
+            );
         }
+    }
+
+    mod text_fallback {
+        use serde_json::Value;
+
+        use crate::{ConvertOptions, Mrkdwn};
+
+        macro_rules! test {
+            ($name:ident, $input:expr, $expected:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Mrkdwn::from($input).text_fallback().unwrap(), $expected);
+                }
+            };
+        }
+
+        test!(
+            flattens_formatting,
+            "# Title\n\nSome **bold**, _italic_, `code`, and [a link](https://x.com).",
+            "Title\nSome bold, italic, code, and a link."
+        );
+        test!(escapes, "1 < 2 & 3 > 2", "1 &lt; 2 &amp; 3 &gt; 2");
+        test!(lists, "- a\n    - b\n- c\n\n3. d\n\n- [x] e", "• a\n    • b\n• c\n3. d\n☑ e");
+        test!(quotes, "> quoted\n>\n> more", "quoted\nmore");
+        test!(code, "```\nlet x = 1;\nlet y = 2;\n```", "let x = 1;\nlet y = 2;");
+        test!(
+            tables,
+            "| Name | Score |\n|-|-|\n| Alice | 10 |\n| Bob | 7 |",
+            "[Table: Name, Score; 2 rows]"
+        );
+        test!(one_row, "| a |\n|-|\n| 1 |", "[Table: a; 1 row]");
+        test!(
+            images,
+            "![A cat](https://x.com/cat.png)\n\nSee ![](https://x.com/dog.png \"Dog\") here.",
+            "[Image: A cat]\nSee [Image: Dog] here."
+        );
+        test!(footnotes, "Text[^note].\n\n[^note]: The **body**.", "Text¹.\n¹ The body.");
+        test!(dates, "Due <time datetime=\"2026-10-17\">Saturday</time>.", "Due 2026-10-17.");
+        test!(dividers_dropped, "a\n\n---\n\nb", "a\nb");
 
         #[test]
-        fn section_over_3000_chars_is_err() {
-            let input = "a".repeat(3000);
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        fn bullets_have_a_single_space() {
+            let options = ConvertOptions::builder().bullets(["-"]).build();
+            assert_eq!(Mrkdwn::from("- a").options(options).text_fallback().unwrap(), "- a");
         }
 
         #[test]
-        fn alt_text_over_2000_chars_is_err() {
-            let input = format!("![{}](https://example.com/x.png)", "a".repeat(2001));
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        fn capped() {
+            let options = ConvertOptions::builder().fallback_chars(10).build();
+            let text = Mrkdwn::from("a".repeat(20).as_str())
+                .options(options)
+                .text_fallback()
+                .unwrap();
+            assert_eq!(text, format!("{}…", "a".repeat(9)));
         }
 
         #[test]
-        fn table_over_100_rows_is_err() {
-            let mut input = String::from("| h |\n|---|\n");
-            for _ in 0..100 {
-                input.push_str("| c |\n");
+        fn capped_by_default() {
+            let text = Mrkdwn::from("a ".repeat(3000).as_str()).text_fallback().unwrap();
+            assert_eq!(text.chars().count(), 4000);
+        }
+
+        #[test]
+        fn payload() {
+            let payload = Mrkdwn::from("Hello **world**").payload_stringify().unwrap();
+            let payload: Value = serde_json::from_str(&payload).unwrap();
+            assert_eq!(payload["text"], "Hello world");
+            assert_eq!(payload["blocks"][0]["text"]["text"], "Hello *world*\n");
+        }
+    }
+
+    mod message_payloads {
+        use serde_json::json;
+
+        use crate::{Block, Destination, Error, Message, Mrkdwn, ResponseType};
+
+        fn message() -> Message {
+            Mrkdwn::from("Hello **world**").message().unwrap().mrkdwn(true)
+        }
+
+        fn blocks() -> serde_json::Value {
+            json!([{ "type": "section", "text": { "type": "mrkdwn", "text": "Hello *world*\n" } }])
+        }
+
+        #[test]
+        fn post_message() {
+            assert_eq!(
+                message()
+                    .channel("C123")
+                    .thread_ts("1700000000.000100")
+                    .unfurl_links(false)
+                    .unfurl_media(false)
+                    .username("bot")
+                    .icon_emoji(":robot_face:")
+                    .to_value(Destination::PostMessage)
+                    .unwrap(),
+                json!({
+                    "channel": "C123",
+                    "blocks": blocks(),
+                    "text": "Hello world",
+                    "thread_ts": "1700000000.000100",
+                    "unfurl_links": false,
+                    "unfurl_media": false,
+                    "mrkdwn": true,
+                    "username": "bot",
+                    "icon_emoji": ":robot_face:",
+                })
+            );
+        }
+
+        #[test]
+        fn webhook() {
+            assert_eq!(
+                message()
+                    .unfurl_links(false)
+                    .unfurl_media(false)
+                    .to_value(Destination::Webhook)
+                    .unwrap(),
+                json!({
+                    "blocks": blocks(),
+                    "text": "Hello world",
+                    "unfurl_links": false,
+                    "unfurl_media": false,
+                    "mrkdwn": true,
+                })
+            );
+        }
+
+        #[test]
+        fn response_url() {
+            assert_eq!(
+                message()
+                    .thread_ts("1700000000.000100")
+                    .response_type(ResponseType::InChannel)
+                    .replace_original(true)
+                    .to_value(Destination::ResponseUrl)
+                    .unwrap(),
+                json!({
+                    "blocks": blocks(),
+                    "text": "Hello world",
+                    "thread_ts": "1700000000.000100",
+                    "mrkdwn": true,
+                    "response_type": "in_channel",
+                    "replace_original": true,
+                })
+            );
+        }
+
+        #[test]
+        fn unset_fields_omitted() {
+            let message = Message::new(vec![Block::Divider]);
+            assert_eq!(
+                message.to_json(Destination::Webhook).unwrap(),
+                r#"{"blocks":[{"type":"divider"}]}"#
+            );
+            assert_eq!(
+                message
+                    .response_type(ResponseType::Ephemeral)
+                    .to_value(Destination::ResponseUrl),
+                Ok(json!({ "blocks": [{ "type": "divider" }], "response_type": "ephemeral" }))
+            );
+        }
+
+        #[test]
+        fn post_message_needs_channel() {
+            let error = Message::new(vec![Block::Divider])
+                .to_json(Destination::PostMessage)
+                .unwrap_err();
+            assert_eq!(error.to_string(), "invalid message: missing channel");
+        }
+
+        #[test]
+        fn rejects_fields_the_destination_does_not_accept() {
+            let cases = [
+                (message().channel("C123"), Destination::Webhook, "channel", "incoming webhooks"),
+                (
+                    message().icon_emoji(":x:"),
+                    Destination::Webhook,
+                    "icon_emoji",
+                    "incoming webhooks",
+                ),
+                (
+                    message().unfurl_links(false),
+                    Destination::ResponseUrl,
+                    "unfurl_links",
+                    "response_url",
+                ),
+                (
+                    message().channel("C123").replace_original(true),
+                    Destination::PostMessage,
+                    "replace_original",
+                    "chat.postMessage",
+                ),
+            ];
+            for (message, destination, field, name) in cases {
+                assert_eq!(
+                    message.to_json(destination).unwrap_err().to_string(),
+                    format!("invalid message: {field} is not accepted by {name}")
+                );
             }
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
         }
 
         #[test]
-        fn table_at_100_rows_is_ok() {
-            let mut input = String::from("| h |\n|---|\n");
-            for _ in 0..99 {
-                input.push_str("| c |\n");
+        fn needs_blocks_or_text() {
+            let error = Message::new(Vec::new()).to_json(Destination::Webhook).unwrap_err();
+            assert!(matches!(error, Error::InvalidMessage { .. }));
+            let text_only = Message::new(Vec::new()).text("hi");
+            assert_eq!(text_only.to_json(Destination::Webhook).unwrap(), r#"{"text":"hi"}"#);
+        }
+    }
+
+    mod surfaces {
+        use serde_json::json;
+
+        use crate::{
+            Block, BlockMode, Error, Limit, LimitKind, Limits, Mrkdwn, OverflowPolicy, Surface,
+            View,
+        };
+
+        fn paragraphs(count: usize) -> String {
+            (0..count).map(|i| format!("p{i}")).collect::<Vec<_>>().join("\n\n")
+        }
+
+        #[test]
+        fn block_limit_per_surface() {
+            assert_eq!(Limits::for_surface(Surface::Message).blocks.max, Some(50));
+            assert_eq!(Limits::for_surface(Surface::Modal).blocks.max, Some(100));
+            assert_eq!(Limits::default().blocks_on(Surface::HomeTab).max, 100);
+        }
+
+        #[test]
+        fn surface_before_limits() {
+            let text = paragraphs(80);
+            let limits = Limits::default().with_policy(OverflowPolicy::Truncate);
+            let blocks = Mrkdwn::from(text.as_str())
+                .surface(Surface::Modal)
+                .limits(limits)
+                .blockify()
+                .unwrap();
+            assert_eq!(blocks.len(), 80);
+        }
+
+        #[test]
+        fn explicit_block_limit_wins() {
+            let text = paragraphs(80);
+            let limits = Limits {
+                blocks: Limit { max: Some(50), policy: OverflowPolicy::Truncate },
+                ..Limits::default()
+            };
+            let view = Mrkdwn::from(text.as_str()).limits(limits).modal("Help").unwrap();
+            assert_eq!(view.blocks().len(), 50);
+        }
+
+        #[test]
+        fn modal_holds_more_blocks_than_a_message() {
+            let text = paragraphs(80);
+            let error = Mrkdwn::from(text.as_str()).blockify().unwrap_err();
+            assert!(matches!(error, Error::LimitExceeded { kind: LimitKind::Blocks, .. }));
+            let blocks = Mrkdwn::from(text.as_str())
+                .surface(Surface::Modal)
+                .blockify()
+                .unwrap();
+            assert_eq!(blocks.len(), 80);
+        }
+
+        #[test]
+        fn validate_uses_the_surface_limit() {
+            let text = paragraphs(120);
+            let violations = Mrkdwn::from(text.as_str())
+                .surface(Surface::HomeTab)
+                .validate()
+                .unwrap();
+            assert_eq!(violations.len(), 1);
+            assert_eq!((violations[0].actual, violations[0].max), (120, 100));
+        }
+
+        #[test]
+        fn tables_are_text_outside_messages() {
+            let text = "| a | b |\n|---|---|\n| 1 | 2 |";
+            let blocks = Mrkdwn::from(text).surface(Surface::Modal).blockify().unwrap();
+            assert!(matches!(blocks.as_slice(), [Block::Section(_)]));
+            let blocks = Mrkdwn::from(text).blockify().unwrap();
+            assert!(matches!(blocks.as_slice(), [Block::Table { .. }]));
+        }
+
+        #[test]
+        fn markdown_blocks_are_not_allowed_in_views() {
+            let error = Mrkdwn::from("Hello")
+                .block_mode(BlockMode::Markdown)
+                .surface(Surface::HomeTab)
+                .blockify()
+                .unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "markdown blocks are not allowed on the Home tab surface"
+            );
+        }
+
+        #[test]
+        fn modal_payload() {
+            let view = Mrkdwn::from("Hello **world**")
+                .modal("Help")
+                .unwrap()
+                .close("Close")
+                .submit("Done")
+                .callback_id("help")
+                .private_metadata("{}")
+                .external_id("help-1");
+            assert_eq!(
+                view.to_value().unwrap(),
+                json!({
+                    "type": "modal",
+                    "title": { "type": "plain_text", "text": "Help" },
+                    "close": { "type": "plain_text", "text": "Close" },
+                    "submit": { "type": "plain_text", "text": "Done" },
+                    "blocks": [
+                        { "type": "section", "text": { "type": "mrkdwn", "text": "Hello *world*\n" } }
+                    ],
+                    "callback_id": "help",
+                    "private_metadata": "{}",
+                    "external_id": "help-1",
+                })
+            );
+        }
+
+        #[test]
+        fn home_tab_payload() {
+            let view = Mrkdwn::from("Hello").home_tab().unwrap();
+            assert_eq!(view.surface(), Surface::HomeTab);
+            assert_eq!(
+                view.to_value().unwrap(),
+                json!({
+                    "type": "home",
+                    "blocks": [
+                        { "type": "section", "text": { "type": "mrkdwn", "text": "Hello\n" } }
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn views_use_their_surface_limit() {
+            let text = paragraphs(80);
+            let limits = Limits::default().with_policy(OverflowPolicy::Error);
+            let view = Mrkdwn::from(text.as_str()).limits(limits).home_tab().unwrap();
+            assert_eq!(view.blocks().len(), 80);
+        }
+
+        #[test]
+        fn long_title_is_invalid() {
+            let view = View::modal("A title that is far too long", Vec::new());
+            assert_eq!(
+                view.to_json().unwrap_err().to_string(),
+                "invalid view: title has 28 characters, Slack allows 1 to 24"
+            );
+        }
+
+        #[test]
+        fn table_block_is_not_allowed_in_a_modal() {
+            let table = Block::Table { column_settings: Vec::new(), rows: Vec::new() };
+            let error = View::modal("Help", vec![table]).to_value().unwrap_err();
+            assert!(matches!(error, Error::BlockNotAllowed { surface: Surface::Modal, .. }));
+        }
+    }
+
+    mod messages {
+        use crate::{Block, Mrkdwn};
+
+        #[test]
+        fn short_document_is_one_message() {
+            let messages = Mrkdwn::from("# Title\n\nBody").blockify_messages().unwrap();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].len(), 3);
+        }
+
+        #[test]
+        fn empty_document_has_no_messages() {
+            assert!(Mrkdwn::from("").blockify_messages().unwrap().is_empty());
+        }
+
+        #[test]
+        fn splits_at_heading_boundaries() {
+            // 30 sections of a heading and a paragraph: 60 blocks, which cannot fit in one
+            // message. Every message must start with a heading.
+            let input = (0..30)
+                .map(|i| format!("## Heading {i}\n\nBody {i}\n"))
+                .collect::<String>();
+            let messages = Mrkdwn::from(input.as_str()).blockify_messages().unwrap();
+            assert_eq!(messages.len(), 2);
+            assert_eq!(messages[0].len(), 50);
+            assert_eq!(messages[1].len(), 10);
+            for message in &messages {
+                assert!(matches!(message[0], Block::Header(_)));
             }
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
         }
 
         #[test]
-        fn table_over_10_columns_is_err() {
-            let header = format!("|{}", " h |".repeat(11));
-            let delimiter = format!("|{}", "---|".repeat(11));
-            let input = format!("{header}\n{delimiter}\n");
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
+        fn keeps_sections_whole() {
+            // A 40-block section followed by a 20-block one: the second section moves to the
+            // next message instead of being cut after its first 10 blocks.
+            let mut input = String::from("## First\n\n");
+            input.push_str(&"para\n\n".repeat(39));
+            input.push_str("## Second\n\n");
+            input.push_str(&"para\n\n".repeat(19));
+            let messages = Mrkdwn::from(input.as_str()).blockify_messages().unwrap();
+            assert_eq!(messages.iter().map(Vec::len).collect::<Vec<_>>(), vec![40, 20]);
         }
 
         #[test]
-        fn over_50_blocks_is_err() {
+        fn splits_long_sections_between_blocks() {
+            let input = "para\n\n".repeat(120);
+            let messages = Mrkdwn::from(input.as_str()).blockify_messages().unwrap();
+            assert_eq!(messages.iter().map(Vec::len).collect::<Vec<_>>(), vec![50, 50, 20]);
+        }
+
+        #[test]
+        fn stringifies_one_payload_per_message() {
             let input = "---\n".repeat(51);
+            let payloads = Mrkdwn::from(input.as_str()).blocks_stringify_messages().unwrap();
+            assert_eq!(payloads.len(), 2);
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&payloads[1]).unwrap(),
+                serde_json::json!({ "blocks": [ { "type": "divider" } ] })
+            );
+        }
+
+        #[test]
+        fn over_limit_block_is_err() {
+            let input = format!("# {}", "a".repeat(151));
+            assert!(Mrkdwn::from(input.as_str()).blockify_messages().is_err());
+        }
+    }
+
+    mod split_long_sections {
+        use crate::{Block, Mrkdwn};
+
+        fn sections(input: &str) -> Vec<String> {
+            Mrkdwn::from(input)
+                .split_long_sections(true)
+                .blockify()
+                .unwrap()
+                .into_iter()
+                .map(|block| match block {
+                    Block::Section(text) => text,
+                    block => panic!("unexpected block: {block}"),
+                })
+                .collect()
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let input = "a".repeat(3000);
             assert!(Mrkdwn::from(input.as_str()).blockify().is_err());
         }
 
         #[test]
-        fn at_50_blocks_is_ok() {
-            let input = "---\n".repeat(50);
-            assert!(Mrkdwn::from(input.as_str()).blockify().is_ok());
+        fn short_section_is_untouched() {
+            assert_eq!(sections("Hello"), vec!["Hello\n"]);
+        }
+
+        #[test]
+        fn splits_at_line_boundaries() {
+            let line = format!("{}\n", "a".repeat(999));
+            let input = line.repeat(4);
+            let sections = sections(&input);
+            assert_eq!(sections, vec![line.repeat(3), line.clone()]);
+        }
+
+        #[test]
+        fn splits_at_word_boundaries() {
+            let input = "word ".repeat(1000);
+            let sections = sections(input.trim_end());
+            assert_eq!(sections.len(), 2);
+            assert!(sections.iter().all(|s| s.chars().count() <= 3000));
+            assert!(sections[0].ends_with("word "));
+            assert_eq!(sections.concat(), format!("{}\n", input.trim_end()));
+        }
+
+        #[test]
+        fn never_cuts_entities() {
+            let input = "&".repeat(1000);
+            let sections = sections(&input);
+            assert_eq!(sections.len(), 2);
+            assert!(sections.iter().all(|s| s.chars().count() <= 3000));
+            assert!(sections.iter().all(|s| s.starts_with("&amp;")));
+        }
+
+        #[test]
+        fn list_items_stay_whole() {
+            let input = (0..300)
+                .map(|i| format!("- item number {i:04}\n"))
+                .collect::<String>();
+            for section in sections(&input) {
+                assert!(section.chars().count() <= 3000);
+                for line in section.lines().filter(|line| !line.is_empty()) {
+                    assert!(line.starts_with("•   item number "), "cut line: {line:?}");
+                }
+            }
+        }
+
+        #[test]
+        fn reopens_code_fences() {
+            let input = format!("```\n{}```", "console.log(1);\n".repeat(300));
+            let sections = sections(&input);
+            assert_eq!(sections.len(), 2);
+            for section in &sections {
+                assert!(section.chars().count() <= 3000);
+                assert!(section.starts_with("```\n"));
+                assert!(section.ends_with("```\n"));
+            }
         }
     }
 
+    #[allow(deprecated)]
     mod mrkdwnify {
         use crate::Mrkdwn;
 
@@ -305,7 +3122,7 @@ Another paragraph.
         test!(
             nested_lists,
             "- First\n    - Second\n        - Third\n    - Fourth\n        - Fifth\n        - Sixth\n- Seventh",
-            "•   First\\n    •   Second\\n        •   Third\\n    •   Fourth\\n        •   Fifth\\n        •   Sixth\\n\\n•   Seventh"
+            "•   First\\n    •   Second\\n        •   Third\\n    •   Fourth\\n        •   Fifth\\n        •   Sixth\\n•   Seventh"
         );
         test!(thematic_breaks, "---", "----------");
         test!(
@@ -315,7 +3132,11 @@ Another paragraph.
         );
         test!(image, "![logo](https://x.com/l.png \"Logo\")", "<https://x.com/l.png|logo>");
         test!(image_without_alt, "![](https://x.com/bare.png)", "<https://x.com/bare.png>");
-        test!(footnotes, "Text[^note].\n\n[^note]: The body.", "Text[^note].\\n[^note]: The body.");
+        test!(
+            footnotes,
+            "Text[^note].\n\n[^note]: The body.",
+            "Text¹.\\n\\n----------\\n¹ The body."
+        );
         test!(
             task_lists,
             "- [ ] First\n- [x] Second\n- [ ] Third",
@@ -358,7 +3179,7 @@ console.log('Hello, mrkdwn!')
 
 Another paragraph.
 "#,
-            "*Heading 1*\\n\\n*Heading 2*\\n\\n*Heading 3*\\n\\nHello, ~Markdown~ *mrkdwn*! and _markdown_.\\n`mrkdwn` is text formatting markup style in <https://slack.com/|Slack>.\\n\\n----------\\n•   First\\n    •   Second\\n        •   Third\\n    •   Fourth\\n        •   Fifth\\n        •   Sixth\\n\\n•   Seventh\\n\\n1.  Ordered list 1\\n    •   Ordered list 1-1\\n        •   Ordered list 1-2\\n\\n2.  Ordered list 2\\n    1.  Ordered list 2-1\\n    2.  Ordered list 2-2\\n\\n3.  Ordered list 3\\n\\n> _This is blockquote._\\n```\\nconsole.log('Hello, mrkdwn!')\\n```\\nAnother paragraph."
+            "*Heading 1*\\n\\n*Heading 2*\\n\\n*Heading 3*\\n\\nHello, ~Markdown~ *mrkdwn*! and _markdown_.\\n`mrkdwn` is text formatting markup style in <https://slack.com/|Slack>.\\n\\n----------\\n•   First\\n    •   Second\\n        •   Third\\n    •   Fourth\\n        •   Fifth\\n        •   Sixth\\n•   Seventh\\n\\n1.  Ordered list 1\\n    •   Ordered list 1-1\\n        •   Ordered list 1-2\\n2.  Ordered list 2\\n    1.  Ordered list 2-1\\n    2.  Ordered list 2-2\\n3.  Ordered list 3\\n\\n> _This is blockquote._\\n```\\nconsole.log('Hello, mrkdwn!')\\n```\\nAnother paragraph."
         );
     }
 }
@@ -0,0 +1,309 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{Block, Span};
+
+// Limits Slack enforces on Block Kit messages. Exceeding any of them makes the API reject the
+// message, so [`crate::Mrkdwn::blockify`] either fits the blocks into them or returns an error
+// instead of emitting an invalid payload, depending on the [`OverflowPolicy`].
+//
+// See <https://docs.slack.dev/reference/block-kit/blocks/>.
+
+/// The most blocks in a message.
+pub(crate) const MAX_BLOCKS: usize = 50;
+/// The most blocks in a modal or Home tab view.
+pub(crate) const MAX_VIEW_BLOCKS: usize = 100;
+/// The most characters in the text of a `header` block.
+pub(crate) const MAX_HEADER_CHARS: usize = 150;
+/// The most characters in the text of a `section` block.
+pub(crate) const MAX_SECTION_CHARS: usize = 3000;
+/// The most characters in the `alt_text` of an `image` block.
+pub(crate) const MAX_ALT_TEXT_CHARS: usize = 2000;
+/// The most rows in a `table` block, header row included.
+pub(crate) const MAX_TABLE_ROWS: usize = 100;
+/// The most columns in a `table` block.
+pub(crate) const MAX_TABLE_COLS: usize = 10;
+/// The most characters across all `markdown` blocks of a message.
+pub(crate) const MAX_MARKDOWN_CHARS: usize = 12000;
+/// The most `table` blocks in a message.
+pub(crate) const MAX_TABLES: usize = 1;
+/// The most characters in the text of a `context` block element.
+pub(crate) const MAX_CONTEXT_CHARS: usize = 3000;
+/// The most elements in a `context` block.
+pub(crate) const MAX_CONTEXT_ELEMENTS: usize = 10;
+
+/// The Slack surface blocks are shown on, which decides how many blocks fit and which block
+/// types are allowed.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Surface {
+    /// A message. This is the default.
+    #[default]
+    Message,
+    /// A modal view.
+    Modal,
+    /// The Home tab of an app.
+    HomeTab,
+}
+
+impl Surface {
+    /// Returns the most blocks the surface holds: 50 in a message, and 100 in a modal or the
+    /// Home tab.
+    pub fn max_blocks(self) -> usize {
+        match self {
+            Surface::Message => MAX_BLOCKS,
+            Surface::Modal | Surface::HomeTab => MAX_VIEW_BLOCKS,
+        }
+    }
+
+    /// Returns whether `block` may be shown on the surface. `table` and `markdown` blocks are
+    /// only allowed in messages.
+    pub fn allows(self, block: &Block) -> bool {
+        self == Surface::Message || !matches!(block, Block::Table { .. } | Block::Markdown(_))
+    }
+}
+
+impl Display for Surface {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Surface::Message => write!(f, "message"),
+            Surface::Modal => write!(f, "modal"),
+            Surface::HomeTab => write!(f, "Home tab"),
+        }
+    }
+}
+
+/// What to do when a block exceeds one of Slack's limits.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// Fail the conversion with an error. This is the default.
+    #[default]
+    Error,
+    /// Cut the content at the limit and mark the cut with a visible `…`.
+    Truncate,
+    /// Spread the content over several blocks (or, for the block count, several messages).
+    Split,
+}
+
+/// A single Slack limit: the maximum allowed and what to do beyond it.
+///
+/// The block count is a `Limit<Option<usize>>`, whose `max` is `None` to use the limit of the
+/// [`Surface`] converted for.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Limit<T = usize> {
+    pub max: T,
+    pub policy: OverflowPolicy,
+}
+
+impl Limit {
+    const fn new(max: usize) -> Self {
+        Self { max, policy: OverflowPolicy::Error }
+    }
+}
+
+/// Identifies one of the limits in [`Limits`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum LimitKind {
+    /// Blocks per message.
+    Blocks,
+    /// Characters of `header` block text.
+    HeaderChars,
+    /// Characters of `section` block text.
+    SectionChars,
+    /// Characters of image `alt_text`.
+    AltTextChars,
+    /// Rows per table, including the header row.
+    TableRows,
+    /// Columns per table.
+    TableCols,
+    /// Characters of `markdown` block text, summed over all of them in a message.
+    MarkdownChars,
+    /// `table` blocks per message.
+    Tables,
+    /// Characters of the text of a `context` block element.
+    ContextChars,
+    /// Elements per `context` block.
+    ContextElements,
+}
+
+/// The limits applied when converting to blocks, with the [`OverflowPolicy`] for each.
+///
+/// The default uses Slack's limits for messages and fails on any of them. What truncating and
+/// splitting mean depends on the limit:
+///
+/// - `blocks` (50, or 100 in a modal or the Home tab, unless `max` overrides the surface's):
+///   truncating drops the trailing blocks. A single message cannot be split, so `Split` behaves
+///   like `Error` here; use [`crate::Mrkdwn::blockify_messages`] instead.
+/// - `header_chars` (150), `section_chars` (3000): truncating cuts the text, splitting spreads it
+///   over several blocks of the same type.
+/// - `alt_text_chars` (2000): an image has a single alt text, so `Split` behaves like `Truncate`.
+/// - `table_rows` (100): truncating drops the trailing rows, splitting makes several tables that
+///   each repeat the header row. Below 2 rows splitting, and at 0 rows truncating, behave like
+///   `Error`.
+/// - `table_cols` (10): truncating drops the trailing columns, splitting makes several tables
+///   that each hold a range of the columns.
+/// - `markdown_chars` (12000): the total over all `markdown` blocks of a message. Truncating cuts
+///   the message at the limit, dropping every block after the cut. Splitting spreads a single
+///   over-long block over several; the total of a message cannot be split, so use
+///   [`crate::Mrkdwn::blockify_messages`] as for `blocks`.
+/// - `tables` (1): truncating renders every Markdown table whose table blocks would go beyond
+///   the limit as text in a `section` block, drawn as [`crate::ConvertOptions::text_tables`]
+///   says. A single message cannot be split, so `Split` behaves like `Error` here;
+///   [`crate::Mrkdwn::blockify_messages`] starts a new message at a table instead. A table
+///   split by `table_rows` or `table_cols` counts as several.
+/// - `context_chars` (3000), `context_elements` (10): the text of each element of the `context`
+///   block holding footnotes, and the elements of the block. Truncating cuts the text or drops
+///   the trailing elements, splitting spreads the text over several elements and the elements
+///   over several `context` blocks.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Limits {
+    pub blocks: Limit<Option<usize>>,
+    pub header_chars: Limit,
+    pub section_chars: Limit,
+    pub alt_text_chars: Limit,
+    pub table_rows: Limit,
+    pub table_cols: Limit,
+    pub markdown_chars: Limit,
+    pub tables: Limit,
+    pub context_chars: Limit,
+    pub context_elements: Limit,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            blocks: Limit { max: None, policy: OverflowPolicy::Error },
+            header_chars: Limit::new(MAX_HEADER_CHARS),
+            section_chars: Limit::new(MAX_SECTION_CHARS),
+            alt_text_chars: Limit::new(MAX_ALT_TEXT_CHARS),
+            table_rows: Limit::new(MAX_TABLE_ROWS),
+            table_cols: Limit::new(MAX_TABLE_COLS),
+            markdown_chars: Limit::new(MAX_MARKDOWN_CHARS),
+            tables: Limit::new(MAX_TABLES),
+            context_chars: Limit::new(MAX_CONTEXT_CHARS),
+            context_elements: Limit::new(MAX_CONTEXT_ELEMENTS),
+        }
+    }
+}
+
+impl Limits {
+    /// Returns Slack's limits for `surface`, failing on all of them, with the block count set
+    /// explicitly. Only the block count differs between surfaces.
+    pub fn for_surface(surface: Surface) -> Self {
+        let limits = Self::default();
+        Self {
+            blocks: Limit { max: Some(surface.max_blocks()), ..limits.blocks },
+            ..limits
+        }
+    }
+
+    /// Returns the block count limit on `surface`: its `max` when set, and otherwise the
+    /// surface's.
+    pub fn blocks_on(&self, surface: Surface) -> Limit {
+        Limit {
+            max: self.blocks.max.unwrap_or(surface.max_blocks()),
+            policy: self.blocks.policy,
+        }
+    }
+
+    /// Returns these limits with `policy` applied to every one of them.
+    pub fn with_policy(self, policy: OverflowPolicy) -> Self {
+        let limit = |limit: Limit| Limit { policy, ..limit };
+        Self {
+            blocks: Limit { policy, ..self.blocks },
+            header_chars: limit(self.header_chars),
+            section_chars: limit(self.section_chars),
+            alt_text_chars: limit(self.alt_text_chars),
+            table_rows: limit(self.table_rows),
+            table_cols: limit(self.table_cols),
+            markdown_chars: limit(self.markdown_chars),
+            tables: limit(self.tables),
+            context_chars: limit(self.context_chars),
+            context_elements: limit(self.context_elements),
+        }
+    }
+
+    /// Returns the limit identified by `kind`. The block count is the one on `surface`, as
+    /// [`Self::blocks_on`] returns it.
+    pub fn get(&self, kind: LimitKind, surface: Surface) -> Limit {
+        match kind {
+            LimitKind::Blocks => self.blocks_on(surface),
+            LimitKind::HeaderChars => self.header_chars,
+            LimitKind::SectionChars => self.section_chars,
+            LimitKind::AltTextChars => self.alt_text_chars,
+            LimitKind::TableRows => self.table_rows,
+            LimitKind::TableCols => self.table_cols,
+            LimitKind::MarkdownChars => self.markdown_chars,
+            LimitKind::Tables => self.tables,
+            LimitKind::ContextChars => self.context_chars,
+            LimitKind::ContextElements => self.context_elements,
+        }
+    }
+}
+
+/// A block exceeding one of the [`Limits`], as reported by [`crate::Mrkdwn::validate`].
+///
+/// - `kind`: the limit that is exceeded.
+/// - `block`: the index of the offending block. For [`LimitKind::Blocks`] and
+///   [`LimitKind::MarkdownChars`], the index of the first block over the limit, and for
+///   [`LimitKind::Tables`], the index of the first table over the limit.
+/// - `actual`: the size of the offending block (characters, rows, or columns), the number of
+///   blocks for [`LimitKind::Blocks`], the total for [`LimitKind::MarkdownChars`], or the
+///   number of tables for [`LimitKind::Tables`].
+/// - `max`: the limit.
+/// - `span`: the Markdown the offending block was converted from, when known.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Violation {
+    pub kind: LimitKind,
+    pub block: usize,
+    pub actual: usize,
+    pub max: usize,
+    pub span: Option<Span>,
+}
+
+/// Records content dropped by [`OverflowPolicy::Truncate`].
+///
+/// - `kind`: the limit that was exceeded.
+/// - `block`: the index of the truncated block in the result. For [`LimitKind::Blocks`], the
+///   index of the `…` marker block that replaces the dropped ones; for
+///   [`LimitKind::MarkdownChars`], the index of the block cut at the limit.
+/// - `actual`: the size before truncation (characters, rows, columns, or blocks).
+/// - `max`: the limit the content was truncated to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Truncation {
+    pub kind: LimitKind,
+    pub block: usize,
+    pub actual: usize,
+    pub max: usize,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "block {}", self.block)?;
+        if let Some(span) = self.span {
+            write!(f, " ({span})")?;
+        }
+        write!(f, ": ")?;
+        write_limit_exceeded(f, self.kind, self.actual, self.max)
+    }
+}
+
+/// Describes a block exceeding a limit, e.g. "table has 11 columns, Slack allows 10".
+pub(crate) fn write_limit_exceeded(
+    f: &mut Formatter<'_>,
+    kind: LimitKind,
+    actual: usize,
+    max: usize,
+) -> fmt::Result {
+    let (subject, unit) = match kind {
+        LimitKind::Blocks => ("message", "blocks"),
+        LimitKind::HeaderChars => ("header text", "characters"),
+        LimitKind::SectionChars => ("section text", "characters"),
+        LimitKind::AltTextChars => ("image alt_text", "characters"),
+        LimitKind::TableRows => ("table", "rows"),
+        LimitKind::TableCols => ("table", "columns"),
+        LimitKind::MarkdownChars => ("markdown text", "characters"),
+        LimitKind::Tables => ("message", "table blocks"),
+        LimitKind::ContextChars => ("context text", "characters"),
+        LimitKind::ContextElements => ("context block", "elements"),
+    };
+    write!(f, "{subject} has {actual} {unit}, Slack allows {max}")
+}
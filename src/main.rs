@@ -4,11 +4,12 @@ use std::{
     fs::read_to_string,
     io::{Read, stdin},
     path::PathBuf,
+    process::exit,
 };
 
 use anyhow::Result;
 use clap::Parser;
-use markdown2mrkdwn::Mrkdwn;
+use markdown2mrkdwn::{BlockMode, MentionMap, Mrkdwn};
 
 #[derive(Debug, Parser)]
 #[clap(about = "Convert markdown to mrkdwn format and dump it to stdout")]
@@ -21,10 +22,38 @@ struct Args {
     /// Whether to convert markdown to mrkdwn blocks. Defaults to plain mrkdwn.
     #[arg(short, long)]
     blocks: bool,
+
+    /// Check the blocks against Slack's limits and print every violation instead of the
+    /// converted output, with warnings such as undefined footnotes on stderr. Exits with a
+    /// non-zero status when there are violations.
+    #[arg(long)]
+    validate: bool,
+
+    /// Lay out text, lists, quotes, and code as rich_text blocks instead of mrkdwn sections.
+    /// Implies --blocks.
+    #[arg(long)]
+    rich_text: bool,
+
+    /// Wrap the Markdown in markdown blocks, which Slack renders itself, instead of mrkdwn
+    /// sections. Implies --blocks.
+    #[arg(long, conflicts_with = "rich_text")]
+    markdown: bool,
+
+    /// Path to a JSON file mapping names to Slack IDs, used to turn @user, #channel, and
+    /// @here handles into mentions. See `MentionMap::from_json` for the format.
+    #[arg(long, value_name = "PATH")]
+    mentions: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
-    let Args { path, blocks } = Args::parse();
+    let Args {
+        path,
+        blocks,
+        validate,
+        rich_text,
+        markdown,
+        mentions,
+    } = Args::parse();
     let input = match path {
         None => {
             let mut buffer = String::new();
@@ -34,20 +63,38 @@ fn main() -> Result<()> {
         Some(p) => read_to_string(p)?,
     };
 
-    print!(
-        "{}",
-        if blocks {
-            Mrkdwn::from(input.as_str()).blocks_stringify()?
-        } else {
-            Mrkdwn::from(input.as_str())
-                .mrkdwnify()?
-                .replace("\\\"", "\"")
-                .replace("\\n", "\n")
-                .replace("&lt;", "<")
-                .replace("&gt;", ">")
-                .replace("&amp;", "&")
+    let mode = if rich_text {
+        BlockMode::RichText
+    } else if markdown {
+        BlockMode::Markdown
+    } else {
+        BlockMode::Mrkdwn
+    };
+    let mention_map = match mentions {
+        Some(path) => Some(MentionMap::from_json(&read_to_string(path)?)?),
+        None => None,
+    };
+    let mut mrkdwn = Mrkdwn::from(input.as_str()).block_mode(mode);
+    if let Some(mention_map) = &mention_map {
+        mrkdwn = mrkdwn.mentions(mention_map);
+    }
+
+    if validate {
+        for diagnostic in mrkdwn.diagnostics()? {
+            eprintln!("warning: {diagnostic}");
+        }
+        let violations = mrkdwn.validate()?;
+        for violation in &violations {
+            println!("{violation}");
         }
-    );
+        if !violations.is_empty() {
+            exit(1);
+        }
+        return Ok(());
+    }
+
+    let blocks = blocks || rich_text || markdown;
+    print!("{}", if blocks { mrkdwn.blocks_stringify()? } else { mrkdwn.mrkdwnify_raw()? });
 
     Ok(())
 }
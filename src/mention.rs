@@ -0,0 +1,249 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Value, json};
+
+use crate::{Error, Result};
+
+/// A Slack mention that a `@name` or `#channel` in the Markdown resolves to.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Mention {
+    /// A user, by user ID (`U…`): `<@U…>`.
+    User(String),
+    /// A channel, by channel ID (`C…`): `<#C…>`.
+    Channel(String),
+    /// A user group, by subteam ID (`S…`): `<!subteam^S…>`.
+    UserGroup(String),
+    /// A broadcast to a channel: `<!here>`, `<!channel>`, or `<!everyone>`.
+    Broadcast(Broadcast),
+}
+
+/// Who a [`Mention::Broadcast`] notifies.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Broadcast {
+    /// The active members of the channel.
+    Here,
+    /// All members of the channel.
+    Channel,
+    /// All members of the workspace.
+    Everyone,
+}
+
+impl Broadcast {
+    /// Returns the broadcast for `here`, `channel`, or `everyone`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "here" => Some(Broadcast::Here),
+            "channel" => Some(Broadcast::Channel),
+            "everyone" => Some(Broadcast::Everyone),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Broadcast::Here => "here",
+            Broadcast::Channel => "channel",
+            Broadcast::Everyone => "everyone",
+        }
+    }
+}
+
+impl Mention {
+    /// Formats the mention as mrkdwn, e.g. `<@U123>`.
+    pub fn to_mrkdwn(&self) -> String {
+        match self {
+            Mention::User(id) => format!("<@{id}>"),
+            Mention::Channel(id) => format!("<#{id}>"),
+            Mention::UserGroup(id) => format!("<!subteam^{id}>"),
+            Mention::Broadcast(broadcast) => format!("<!{}>", broadcast.name()),
+        }
+    }
+
+    /// Builds the `rich_text` section element for the mention.
+    pub(crate) fn to_element(&self) -> Value {
+        match self {
+            Mention::User(id) => json!({ "type": "user", "user_id": id }),
+            Mention::Channel(id) => json!({ "type": "channel", "channel_id": id }),
+            Mention::UserGroup(id) => json!({ "type": "usergroup", "usergroup_id": id }),
+            Mention::Broadcast(broadcast) => {
+                json!({ "type": "broadcast", "range": broadcast.name() })
+            }
+        }
+    }
+}
+
+/// Resolves the `@name` and `#channel` handles written in Markdown into Slack mentions.
+///
+/// Handles the resolver does not know are kept as literal text, so an unresolved `@name` never
+/// turns into a broken mention.
+pub trait MentionResolver {
+    /// Resolves `@name` (given without the `@`) into a user, a user group, or a broadcast such
+    /// as `@here`.
+    fn resolve_user(&self, name: &str) -> Option<Mention>;
+
+    /// Resolves `#name` (given without the `#`) into a channel.
+    fn resolve_channel(&self, name: &str) -> Option<Mention>;
+}
+
+/// A [`MentionResolver`] backed by fixed name-to-ID maps, built in code or loaded from JSON
+/// with [`MentionMap::from_json`].
+///
+/// Broadcasts notify many people, so `@here`, `@channel`, and `@everyone` are only resolved
+/// when explicitly allowed.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MentionMap {
+    users: HashMap<String, String>,
+    channels: HashMap<String, String>,
+    usergroups: HashMap<String, String>,
+    broadcasts: HashSet<Broadcast>,
+}
+
+impl MentionMap {
+    /// Returns an empty map, which resolves nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `@name` to the user `id`.
+    pub fn user(mut self, name: impl Into<String>, id: impl Into<String>) -> Self {
+        self.users.insert(name.into(), id.into());
+        self
+    }
+
+    /// Maps `#name` to the channel `id`.
+    pub fn channel(mut self, name: impl Into<String>, id: impl Into<String>) -> Self {
+        self.channels.insert(name.into(), id.into());
+        self
+    }
+
+    /// Maps `@name` to the user group (subteam) `id`.
+    pub fn usergroup(mut self, name: impl Into<String>, id: impl Into<String>) -> Self {
+        self.usergroups.insert(name.into(), id.into());
+        self
+    }
+
+    /// Allows `@here`, `@channel`, or `@everyone` to notify.
+    pub fn broadcast(mut self, broadcast: Broadcast) -> Self {
+        self.broadcasts.insert(broadcast);
+        self
+    }
+
+    /// Loads a map from JSON of the form:
+    ///
+    /// ```json
+    /// {
+    ///   "users": { "alice": "U123" },
+    ///   "channels": { "deploys": "C123" },
+    ///   "usergroups": { "oncall": "S123" },
+    ///   "broadcasts": ["here"]
+    /// }
+    /// ```
+    ///
+    /// Every key is optional.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let invalid = |message: String| Error::InvalidMentionMap { message };
+        let value: Value =
+            serde_json::from_str(json).map_err(|error| invalid(error.to_string()))?;
+        if !value.is_object() {
+            return Err(invalid("expected an object".to_string()));
+        }
+
+        let names = |key: &str| -> Result<HashMap<String, String>> {
+            match &value[key] {
+                Value::Null => Ok(HashMap::new()),
+                Value::Object(map) => map
+                    .iter()
+                    .map(|(name, id)| match id.as_str() {
+                        Some(id) => Ok((name.clone(), id.to_string())),
+                        None => Err(invalid(format!("{key}.{name} is not a string"))),
+                    })
+                    .collect(),
+                _ => Err(invalid(format!("{key} is not an object"))),
+            }
+        };
+        let broadcasts = match &value["broadcasts"] {
+            Value::Null => HashSet::new(),
+            Value::Array(names) => names
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .and_then(Broadcast::from_name)
+                        .ok_or_else(|| invalid(format!("unknown broadcast {name}")))
+                })
+                .collect::<Result<_>>()?,
+            _ => return Err(invalid("broadcasts is not an array".to_string())),
+        };
+
+        Ok(Self {
+            users: names("users")?,
+            channels: names("channels")?,
+            usergroups: names("usergroups")?,
+            broadcasts,
+        })
+    }
+}
+
+impl MentionResolver for MentionMap {
+    fn resolve_user(&self, name: &str) -> Option<Mention> {
+        if let Some(broadcast) = Broadcast::from_name(name) {
+            return self
+                .broadcasts
+                .contains(&broadcast)
+                .then_some(Mention::Broadcast(broadcast));
+        }
+        self.users
+            .get(name)
+            .map(|id| Mention::User(id.clone()))
+            .or_else(|| self.usergroups.get(name).map(|id| Mention::UserGroup(id.clone())))
+    }
+
+    fn resolve_channel(&self, name: &str) -> Option<Mention> {
+        self.channels.get(name).map(|id| Mention::Channel(id.clone()))
+    }
+}
+
+/// A piece of text, either literal or a resolved mention.
+pub(crate) enum Piece<'t> {
+    Text(&'t str),
+    Mention(Mention),
+}
+
+/// Cuts `text` into literal pieces and the mentions `resolver` resolves.
+///
+/// A handle starts with `@` or `#` at the start of the text or after a character that cannot
+/// be part of a name, so `a@b.com` is not a mention. It runs over letters, digits, `.`, `_`,
+/// and `-`, without the trailing `.` or `-` that usually end a sentence.
+pub(crate) fn split<'t>(text: &'t str, resolver: &dyn MentionResolver) -> Vec<Piece<'t>> {
+    let is_name = |c: char| c.is_alphanumeric() || matches!(c, '.' | '_' | '-');
+    let mut pieces = Vec::new();
+    let mut literal = 0;
+    let mut previous: Option<char> = None;
+
+    for (start, sigil) in text.char_indices() {
+        let after_boundary = previous.is_none_or(|c| !(is_name(c) || matches!(c, '@' | '#' | '/')));
+        previous = Some(sigil);
+        if !matches!(sigil, '@' | '#') || !after_boundary || start < literal {
+            continue;
+        }
+
+        let rest = &text[start + 1..];
+        let len = rest.find(|c: char| !is_name(c)).unwrap_or(rest.len());
+        let name = rest[..len].trim_end_matches(['.', '-']);
+        if name.is_empty() {
+            continue;
+        }
+        let mention =
+            if sigil == '@' { resolver.resolve_user(name) } else { resolver.resolve_channel(name) };
+        if let Some(mention) = mention {
+            if literal < start {
+                pieces.push(Piece::Text(&text[literal..start]));
+            }
+            pieces.push(Piece::Mention(mention));
+            literal = start + 1 + name.len();
+        }
+    }
+    if literal < text.len() {
+        pieces.push(Piece::Text(&text[literal..]));
+    }
+    pieces
+}
@@ -0,0 +1,244 @@
+//! Message payloads for the Slack APIs that post Block Kit messages.
+
+use std::fmt::{self, Display, Formatter};
+
+use serde_json::{Map, Value, json};
+
+use crate::{Block, Error, Result};
+
+/// Where a [`Message`] is sent, which decides the fields its payload may hold.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Destination {
+    /// The [`chat.postMessage`](https://docs.slack.dev/reference/methods/chat.postMessage)
+    /// method, which requires a channel.
+    PostMessage,
+    /// An [incoming webhook], which posts to the channel it was created for.
+    ///
+    /// [incoming webhook]: https://docs.slack.dev/messaging/sending-messages-using-incoming-webhooks/
+    Webhook,
+    /// The `response_url` of a slash command or interaction.
+    ResponseUrl,
+}
+
+/// Who sees a reply sent to a `response_url`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ResponseType {
+    /// Only the user who invoked the command. This is Slack's default.
+    #[default]
+    Ephemeral,
+    /// Everyone in the channel.
+    InChannel,
+}
+
+impl Destination {
+    /// Returns the top-level fields the destination accepts besides `blocks`, `text`, and
+    /// `mrkdwn`.
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            Destination::PostMessage => {
+                &["channel", "thread_ts", "unfurl_links", "unfurl_media", "username", "icon_emoji"]
+            }
+            Destination::Webhook => &["unfurl_links", "unfurl_media"],
+            Destination::ResponseUrl => &["thread_ts", "response_type", "replace_original"],
+        }
+    }
+}
+
+impl Display for Destination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Destination::PostMessage => write!(f, "chat.postMessage"),
+            Destination::Webhook => write!(f, "incoming webhooks"),
+            Destination::ResponseUrl => write!(f, "response_url"),
+        }
+    }
+}
+
+impl ResponseType {
+    fn as_str(self) -> &'static str {
+        match self {
+            ResponseType::Ephemeral => "ephemeral",
+            ResponseType::InChannel => "in_channel",
+        }
+    }
+}
+
+/// A Slack message payload: blocks, the text fallback, and the fields that control where and
+/// how the message is posted. Built with [`crate::Mrkdwn::message`] or [`Message::new`], then
+/// serialized for a [`Destination`].
+///
+/// Fields left unset are omitted from the payload, so Slack's defaults apply. Setting a field
+/// the destination does not accept makes [`Message::to_value`] fail. The destinations accept:
+///
+/// - [`Destination::PostMessage`]: every field but `response_type` and `replace_original`.
+/// - [`Destination::Webhook`]: `blocks`, `text`, `unfurl_links`, `unfurl_media`, and `mrkdwn`.
+/// - [`Destination::ResponseUrl`]: `blocks`, `text`, `thread_ts`, `mrkdwn`, `response_type`,
+///   and `replace_original`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Message {
+    blocks: Vec<Block>,
+    text: Option<String>,
+    channel: Option<String>,
+    thread_ts: Option<String>,
+    unfurl_links: Option<bool>,
+    unfurl_media: Option<bool>,
+    mrkdwn: Option<bool>,
+    response_type: Option<ResponseType>,
+    replace_original: Option<bool>,
+    username: Option<String>,
+    icon_emoji: Option<String>,
+}
+
+impl Message {
+    /// Creates a message holding `blocks`, with every other field unset.
+    pub fn new(blocks: Vec<Block>) -> Self {
+        Self { blocks, ..Self::default() }
+    }
+
+    /// Returns the blocks of the message.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Sets the top-level `text`, which Slack shows in notifications, to screen readers, and in
+    /// clients that cannot render blocks.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Sets the channel ID (or name) to post to.
+    pub fn channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    /// Sets the `ts` of the parent message to reply in its thread.
+    pub fn thread_ts(mut self, thread_ts: impl Into<String>) -> Self {
+        self.thread_ts = Some(thread_ts.into());
+        self
+    }
+
+    /// Sets whether links to text content are unfurled.
+    pub fn unfurl_links(mut self, unfurl: bool) -> Self {
+        self.unfurl_links = Some(unfurl);
+        self
+    }
+
+    /// Sets whether links to media content are unfurled.
+    pub fn unfurl_media(mut self, unfurl: bool) -> Self {
+        self.unfurl_media = Some(unfurl);
+        self
+    }
+
+    /// Sets whether the `text` is read as mrkdwn. Blocks are not affected.
+    pub fn mrkdwn(mut self, mrkdwn: bool) -> Self {
+        self.mrkdwn = Some(mrkdwn);
+        self
+    }
+
+    /// Sets who sees a reply sent to a `response_url`.
+    pub fn response_type(mut self, response_type: ResponseType) -> Self {
+        self.response_type = Some(response_type);
+        self
+    }
+
+    /// Sets whether a reply sent to a `response_url` replaces the message it came from.
+    pub fn replace_original(mut self, replace: bool) -> Self {
+        self.replace_original = Some(replace);
+        self
+    }
+
+    /// Sets the bot's name shown on the message, which needs the `chat:write.customize` scope.
+    pub fn username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Sets the emoji shown as the bot's icon, e.g. `:robot_face:`, which needs the
+    /// `chat:write.customize` scope.
+    pub fn icon_emoji(mut self, icon_emoji: impl Into<String>) -> Self {
+        self.icon_emoji = Some(icon_emoji.into());
+        self
+    }
+
+    /// Builds the JSON payload for `destination`.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`Error::InvalidMessage`] for [`Destination::PostMessage`] without a
+    /// channel, for a message with neither blocks nor text, and for a message with a field the
+    /// destination does not accept.
+    pub fn to_value(&self, destination: Destination) -> Result<Value> {
+        if destination == Destination::PostMessage && self.channel.is_none() {
+            return Err(Error::InvalidMessage { message: "missing channel".to_string() });
+        }
+        if self.blocks.is_empty() && self.text.is_none() {
+            return Err(Error::InvalidMessage { message: "missing blocks and text".to_string() });
+        }
+        let set = [
+            ("channel", self.channel.is_some()),
+            ("thread_ts", self.thread_ts.is_some()),
+            ("unfurl_links", self.unfurl_links.is_some()),
+            ("unfurl_media", self.unfurl_media.is_some()),
+            ("response_type", self.response_type.is_some()),
+            ("replace_original", self.replace_original.is_some()),
+            ("username", self.username.is_some()),
+            ("icon_emoji", self.icon_emoji.is_some()),
+        ];
+        if let Some((field, _)) = set
+            .iter()
+            .find(|(field, set)| *set && !destination.fields().contains(field))
+        {
+            return Err(Error::InvalidMessage {
+                message: format!("{field} is not accepted by {destination}"),
+            });
+        }
+
+        let mut payload = Map::new();
+        let mut insert = |key: &str, value: Option<Value>| {
+            if let Some(value) = value {
+                payload.insert(key.to_string(), value);
+            }
+        };
+        let string = |value: &Option<String>| value.as_ref().map(|value| json!(value));
+        let bool = |value: Option<bool>| value.map(Value::Bool);
+
+        if !self.blocks.is_empty() {
+            let blocks = self.blocks.iter().cloned().map(Value::from).collect();
+            insert("blocks", Some(Value::Array(blocks)));
+        }
+        insert("text", string(&self.text));
+        insert("mrkdwn", bool(self.mrkdwn));
+        match destination {
+            Destination::PostMessage => {
+                insert("channel", string(&self.channel));
+                insert("thread_ts", string(&self.thread_ts));
+                insert("unfurl_links", bool(self.unfurl_links));
+                insert("unfurl_media", bool(self.unfurl_media));
+                insert("username", string(&self.username));
+                insert("icon_emoji", string(&self.icon_emoji));
+            }
+            Destination::Webhook => {
+                insert("unfurl_links", bool(self.unfurl_links));
+                insert("unfurl_media", bool(self.unfurl_media));
+            }
+            Destination::ResponseUrl => {
+                insert("thread_ts", string(&self.thread_ts));
+                let response_type = self.response_type.map(|value| json!(value.as_str()));
+                insert("response_type", response_type);
+                insert("replace_original", bool(self.replace_original));
+            }
+        }
+        Ok(Value::Object(payload))
+    }
+
+    /// Serializes the payload for `destination` into a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::to_value`].
+    pub fn to_json(&self, destination: Destination) -> Result<String> {
+        Ok(self.to_value(destination)?.to_string())
+    }
+}
@@ -1,23 +1,24 @@
-use anyhow::{Result, anyhow};
 use markdown::{
     ParseOptions,
-    mdast::{AlignKind, Image, List, Node, Table},
-    to_mdast,
+    mdast::{AlignKind, FootnoteReference, Heading, Image, List, ListItem, Node, Paragraph, Table},
+    to_mdast, unist,
 };
-use serde_json::{Map, Value, json, to_string};
+use serde_json::{Map, Value, json};
 
-use crate::Block;
+use crate::{
+    Block, ColumnSettings, ConvertOptions, Destination, Diagnostic, DividerStyle, Error,
+    HeadingStyle, ImageStyle, MentionResolver, Message, Result, Span, Surface, TableCells,
+    TableFallback, TextTableStyle, View,
+    date::{self, Date},
+    emoji,
+    footnote::{self, Footnote},
+    gfm,
+    limits::{Limit, LimitKind, Limits, OverflowPolicy, Truncation, Violation},
+    mention, reference, text,
+};
 
-/// Limits Slack enforces on Block Kit messages. Exceeding any of them makes the API reject the
-/// message, so [`Mrkdwn::blockify`] returns an error instead of emitting an invalid payload.
-///
-/// See <https://docs.slack.dev/reference/block-kit/blocks/>.
-const MAX_BLOCKS: usize = 50;
-const MAX_HEADER_CHARS: usize = 150;
-const MAX_SECTION_CHARS: usize = 3000;
-const MAX_ALT_TEXT_CHARS: usize = 2000;
-const MAX_TABLE_ROWS: usize = 100;
-const MAX_TABLE_COLS: usize = 10;
+/// A block together with the span of the Markdown node it was converted from.
+type Located = (Block, Option<Span>);
 
 /// `Mrkdwn` is a public struct for handling GitHub Flavored Markdown text.
 /// Note that the `text` field is not accessible from outside.
@@ -25,26 +26,150 @@ const MAX_TABLE_COLS: usize = 10;
 /// # Fields
 ///
 /// - `text: &'a str` - A GitHub Flavored Markdown.
+/// - `limits: Limits` - The Slack limits blocks are fitted into.
+/// - `mode: BlockMode` - How text content is laid out in blocks.
+/// - `options: ConvertOptions` - The rendering choices for headings, lists, dividers, and images.
+/// - `mentions: Option<&'a dyn MentionResolver>` - Resolves `@name` and `#channel` handles.
+/// - `surface: Surface` - Where the blocks are shown, which decides the block types allowed.
+#[derive(Clone)]
 pub struct Mrkdwn<'a> {
     /// Represents the markdown text.
     text: &'a str,
+    /// Represents the Slack limits and what to do when a block exceeds one of them.
+    limits: Limits,
+    /// Represents which block type text content is converted into.
+    mode: BlockMode,
+    /// Represents how individual Markdown constructs are rendered.
+    options: ConvertOptions,
+    /// Represents the resolver turning handles into Slack mentions, if any.
+    mentions: Option<&'a dyn MentionResolver>,
+    /// Represents the surface the blocks are shown on.
+    surface: Surface,
 }
 
 impl<'a> From<&'a str> for Mrkdwn<'a> {
     /// Constructs a new instance of `Mrkdwn` from the given GitHub Flavored Markdown text.
     fn from(text: &'a str) -> Self {
-        Self { text }
+        Self {
+            text,
+            limits: Limits::default(),
+            mode: BlockMode::default(),
+            options: ConvertOptions::default(),
+            mentions: None,
+            surface: Surface::default(),
+        }
     }
 }
 
+/// The result of [`Mrkdwn::convert`]: the blocks, what was truncated to fit them into the
+/// configured [`Limits`], and the problems in the Markdown that were worked around.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Conversion {
+    pub blocks: Vec<Block>,
+    pub truncations: Vec<Truncation>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Which block type [`Mrkdwn::blockify`] lays text content out in.
+///
+/// Headers, dividers, images, and tables are the same in both modes.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum BlockMode {
+    /// `section` blocks of mrkdwn text, with lists drawn as `•` bullets and quotes as `>`
+    /// lines. This is the default.
+    #[default]
+    Mrkdwn,
+    /// [`rich_text`] blocks: lists become `rich_text_list` elements with native nesting and
+    /// numbering, quotes `rich_text_quote`, and code blocks `rich_text_preformatted`. Native
+    /// lists wrap and render correctly on mobile, which drawn bullets do not.
+    ///
+    /// [`rich_text`]: https://docs.slack.dev/reference/block-kit/blocks/rich-text-block/
+    RichText,
+    /// [`markdown`] blocks holding the Markdown as normalized GFM, which Slack renders itself.
+    /// Reference-style links are resolved, and HTML is dropped but for `<br>` line breaks and
+    /// `<time>` dates, which are written as their fallback text. Consecutive
+    /// top-level nodes share a block; only top-level tables and images, which the markdown
+    /// block does not render, become table and image blocks. The text of all markdown blocks
+    /// in a message is limited to 12000 characters in total.
+    ///
+    /// [`markdown`]: https://docs.slack.dev/reference/block-kit/blocks/markdown-block/
+    Markdown,
+}
+
 impl<'a> Mrkdwn<'a> {
-    /// This method is responsible for markdownifying the text in `self`.
+    /// Sets whether a section whose text exceeds Slack's 3000 character limit is split across
+    /// several `section` blocks instead of making [`Self::blockify`] fail. Disabled by default.
+    ///
+    /// Text is split at paragraph boundaries first, then at line boundaries (so list items are
+    /// never cut mid-line), and only then between words. A fenced code block cut in two is
+    /// closed at the end of the first block and reopened at the start of the next.
+    ///
+    /// This is a shorthand for setting the `section_chars` policy of [`Self::limits`] to
+    /// [`OverflowPolicy::Split`] (or back to [`OverflowPolicy::Error`]).
+    pub fn split_long_sections(mut self, split: bool) -> Self {
+        self.limits.section_chars.policy =
+            if split { OverflowPolicy::Split } else { OverflowPolicy::Error };
+        self
+    }
+
+    /// Sets the limits blocks are fitted into, and whether exceeding each of them fails the
+    /// conversion, truncates the content, or splits it. Defaults to Slack's limits for the
+    /// [`Self::surface`], failing on all of them.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets which block type text content is converted into by [`Self::blockify`] and the
+    /// methods built on it. Defaults to [`BlockMode::Mrkdwn`]. [`Self::mrkdwnify_raw`] is not
+    /// affected.
+    pub fn block_mode(mut self, mode: BlockMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the surface the blocks are shown on. Defaults to [`Surface::Message`].
+    ///
+    /// The surface decides the block count limit, unless [`Self::limits`] sets its `max`.
+    /// Tables are rendered as text outside messages, which do not allow `table` blocks, and
+    /// [`BlockMode::Markdown`] fails with [`Error::BlockNotAllowed`] there.
+    pub fn surface(mut self, surface: Surface) -> Self {
+        self.surface = surface;
+        self
+    }
+
+    /// Sets the rendering choices for headings, lists, dividers, and images. Defaults to
+    /// [`ConvertOptions::default`].
+    pub fn options(mut self, options: ConvertOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the resolver consulted for `@name`, `#channel`, `@here`, and user group handles in
+    /// text. Resolved handles become `<@U…>`, `<#C…>`, `<!here>`, or `<!subteam^S…>` in mrkdwn,
+    /// and `user`, `channel`, `broadcast`, or `usergroup` elements in `rich_text`. Handles in
+    /// code, in link text, and in [`BlockMode::Markdown`] blocks are left as they are. Without a
+    /// resolver, no handle is resolved.
+    pub fn mentions(mut self, resolver: &'a dyn MentionResolver) -> Self {
+        self.mentions = Some(resolver);
+        self
+    }
+
+    /// Converts the provided text into Slack mrkdwn, ready to be used as the `text` of a message
+    /// or a `mrkdwn` text object.
+    ///
+    /// `:shortcode:` emoji are written with Slack's names, and Unicode emoji are converted
+    /// according to [`ConvertOptions::emoji`]. An inline `<time datetime="2026-10-17T12:00Z">`
+    /// tag becomes a `<!date^…>` token, which Slack shows in each reader's time zone. Footnote
+    /// references are numbered, and the definitions follow the text after a divider line in the
+    /// style of [`ConvertOptions::divider`] (`----------` by default), or after a blank line when
+    /// dividers are omitted.
     ///
     /// # Returns
     ///
-    /// - `Ok(String)`: If the process is successful, this method will return a markdownified
-    ///   version of `self.text`.
-    /// - `Err(String)`: In case of an error during the process, it returns an Error.
+    /// - `Ok(String)`: If the process is successful, this method will return a mrkdwn version
+    ///   of `self.text`, with `&`, `<`, and `>` escaped as Slack requires and nothing else.
+    /// - `Err(Error)`: In case of an error during the process, it returns an [`Error`].
     ///
     /// # Errors
     ///
@@ -52,21 +177,48 @@ impl<'a> Mrkdwn<'a> {
     ///
     /// - The text cannot be parsed into a Markdown abstract syntax tree.
     /// - The root node has no children elements.
-    pub fn mrkdwnify(&self) -> Result<String> {
-        let ast = to_mdast(self.text, &ParseOptions::gfm())
-            .map_err(|e| anyhow!("Failed to parse markdown: {e}"))?;
+    pub fn mrkdwnify_raw(&self) -> Result<String> {
+        let mut root = self.parse()?;
+        let footnotes = footnote::extract(&mut root, self.options.footnotes(), &mut Vec::new());
+
+        let mut result = self.transform_to_mrkdwn(&root);
+        if !footnotes.is_empty() {
+            let lines = self.footnote_lines(&footnotes);
+            let separator = self.divider_to_mrkdwn().unwrap_or_else(|| "\n".to_string());
+            result = format!("{result}{separator}{}", lines.join("\n"));
+        }
 
-        let root = ast.children().ok_or_else(|| anyhow!("no input?"))?;
+        Ok(result.trim().to_string())
+    }
 
-        let mut result = self.transform_to_mrkdwn(root);
-        result = result
-            .trim()
+    /// Converts the provided text into Slack mrkdwn like [`Self::mrkdwnify_raw`], serialized
+    /// as a JSON string literal, quotes included, to be embedded in a JSON payload.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::mrkdwnify_raw`].
+    pub fn mrkdwnify_json(&self) -> Result<String> {
+        Ok(Value::String(self.mrkdwnify_raw()?).to_string())
+    }
+
+    /// Converts the provided text into Slack mrkdwn like [`Self::mrkdwnify_raw`], with `"` and
+    /// newlines escaped as `\"` and `\n`.
+    ///
+    /// Other characters a JSON string needs escaped, such as backslashes and tabs, are left as
+    /// they are, so the result is neither raw mrkdwn nor a valid JSON string. Prefer
+    /// [`Self::mrkdwnify_raw`], or [`Self::mrkdwnify_json`] to embed the text in JSON.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::mrkdwnify_raw`].
+    #[deprecated(note = "use mrkdwnify_raw or mrkdwnify_json")]
+    pub fn mrkdwnify(&self) -> Result<String> {
+        Ok(self
+            .mrkdwnify_raw()?
             .replace('"', "\\\"")
             .replace('\n', "\\n")
             .trim_end_matches("\\n")
-            .to_string();
-
-        Ok(result)
+            .to_string())
     }
 
     /// Converts the provided text into a Slack Block Kit blocks.
@@ -75,14 +227,13 @@ impl<'a> Mrkdwn<'a> {
     /// # Returns
     ///
     /// - `Ok(String)`: If the process is successful, this method will return a Slack blocks.
-    /// - `Err(String)`: In case of an error during the process, it returns an Error.
+    /// - `Err(Error)`: In case of an error during the process, it returns an [`Error`].
     ///
     /// # References
     ///
     /// - [Block Kit | Slack](https://api.slack.com/block-kit)
     pub fn blocks_stringify(&self) -> Result<String> {
-        let blocks: Vec<Value> = self.blockify()?.into_iter().map(Value::from).collect();
-        Ok(format!(r#"{{ "blocks": {} }}"#, to_string(&blocks)?))
+        Ok(Self::stringify(self.blockify()?))
     }
 
     /// Converts the provided text into a Slack Block Kit blocks.
@@ -90,70 +241,732 @@ impl<'a> Mrkdwn<'a> {
     /// # Returns
     ///
     /// - `Ok(Vec<Block>)`: If the process is successful, this method will return a Vec of Block.
-    /// - `Err(String)`: In case of an error during the process, it returns an Error.
+    /// - `Err(Error)`: In case of an error during the process, it returns an [`Error`].
     ///
     /// # Errors
     ///
     /// Besides parse failures, this returns an error when the result would exceed a limit Slack
     /// enforces: 50 blocks per message, 150 characters of header text, 3000 characters of
-    /// section text, 2000 characters of image `alt_text`, or 100 rows / 10 columns per table.
+    /// section text, 2000 characters of image `alt_text`, 100 rows / 10 columns per table,
+    /// 12000 characters of markdown text per message, or one table per message.
+    /// Set a different [`OverflowPolicy`] through [`Self::limits`] to truncate or split instead.
     pub fn blockify(&self) -> Result<Vec<Block>> {
-        let ast = to_mdast(self.text, &ParseOptions::gfm())
-            .map_err(|e| anyhow!("Failed to parse markdown: {e}"))?;
+        Ok(self.convert()?.blocks)
+    }
+
+    /// Converts the provided text into Slack Block Kit blocks like [`Self::blockify`], also
+    /// reporting the content truncated to fit into the configured [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::blockify`].
+    pub fn convert(&self) -> Result<Conversion> {
+        let mut diagnostics = Vec::new();
+        let (root, footnotes) = self.parse_blocks(&mut diagnostics)?;
+
+        let mut blocks = self.transform_to_blocks(&root);
+        blocks.extend(self.footnote_block(&footnotes));
+        let mut truncations = Vec::new();
+        let blocks = self.fit_blocks(blocks, &mut truncations);
+        let blocks = self.fit_markdown_chars(blocks, &mut truncations);
+        let blocks = self.fit_block_count(blocks, &mut truncations);
+        self.validate_blocks(&blocks)?;
+        let blocks = blocks.into_iter().map(|(block, _)| block).collect();
+        Ok(Conversion { blocks, truncations, diagnostics })
+    }
+
+    /// Reports the problems in the Markdown that the conversion works around instead of failing
+    /// on, such as footnote references without a definition.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when the text cannot be parsed.
+    pub fn diagnostics(&self) -> Result<Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        footnote::extract(&mut self.parse()?, self.options.footnotes(), &mut diagnostics);
+        Ok(diagnostics)
+    }
+
+    /// Checks the blocks the provided text converts into against the configured [`Limits`],
+    /// reporting every violation instead of stopping at the first one like [`Self::blockify`].
+    ///
+    /// Overflow policies other than [`OverflowPolicy::Error`] are applied first, so only the
+    /// violations [`Self::blockify`] would fail on are reported.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Vec<Violation>)`: Every violation, in block order. Empty when the blocks are valid.
+    /// - `Err(Error)`: When the text cannot be parsed.
+    pub fn validate(&self) -> Result<Vec<Violation>> {
+        let (root, footnotes) = self.parse_blocks(&mut Vec::new())?;
+
+        let mut blocks = self.transform_to_blocks(&root);
+        blocks.extend(self.footnote_block(&footnotes));
+        let blocks = self.fit_blocks(blocks, &mut Vec::new());
+        let blocks = self.fit_markdown_chars(blocks, &mut Vec::new());
+        let blocks = self.fit_block_count(blocks, &mut Vec::new());
+        Ok(self.violations(&blocks))
+    }
+
+    /// Converts the provided text into one or more Slack messages, each a list of blocks.
+    ///
+    /// Unlike [`Self::blockify`], a document producing more than 50 blocks does not fail: the
+    /// blocks are spread over as many messages as needed. Splits happen before headings and
+    /// thematic breaks where possible, so a section (and any table in it) stays in one message;
+    /// only a section that is itself longer than 50 blocks, or holds more than one table, is cut
+    /// between two of its blocks.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Vec<Vec<Block>>)`: One list of blocks per message, in document order.
+    /// - `Err(Error)`: In case of an error during the process, it returns an [`Error`].
+    ///
+    /// # Errors
+    ///
+    /// Besides parse failures, this returns an error when a single block exceeds a limit Slack
+    /// enforces, as described in [`Self::blockify`]. The policies for the block count and the
+    /// table count are ignored: messages are always split.
+    pub fn blockify_messages(&self) -> Result<Vec<Vec<Block>>> {
+        let (root, footnotes) = self.parse_blocks(&mut Vec::new())?;
+
+        let mut sections: Vec<Vec<Located>> = Self::split_sections(&root)
+            .into_iter()
+            .map(|nodes| self.fit_blocks(self.transform_to_blocks(nodes), &mut Vec::new()))
+            .collect();
+        sections.extend(self.footnote_block(&footnotes).map(|block| vec![block]));
+
+        let messages = self.pack_messages(sections);
+        for message in &messages {
+            self.validate_blocks(message)?;
+        }
+        Ok(messages
+            .into_iter()
+            .map(|message| message.into_iter().map(|(block, _)| block).collect())
+            .collect())
+    }
+
+    /// Like [`Self::blocks_stringify`], but emits one `{ "blocks": [...] }` payload per message
+    /// as returned by [`Self::blockify_messages`].
+    pub fn blocks_stringify_messages(&self) -> Result<Vec<String>> {
+        Ok(self.blockify_messages()?.into_iter().map(Self::stringify).collect())
+    }
+
+    /// Renders the provided text as the text fallback Slack uses for a message's `text`: in
+    /// notifications, for screen readers, and in clients that cannot render blocks.
+    ///
+    /// Formatting is flattened the way link text in `rich_text` is, with one line per
+    /// paragraph, list items marked with their bullet or number, and footnotes listed at the
+    /// end. Tables and images are described, e.g. `[Table: Name, Score; 2 rows]` and
+    /// `[Image: A cat]`. The result is still mrkdwn, since Slack reads `text` as mrkdwn: `&`,
+    /// `<`, and `>` are escaped, but literal `*`, `_`, and `~` are kept and may be formatted.
+    /// The result is cut to [`ConvertOptions::fallback_chars`].
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when the text cannot be parsed.
+    pub fn text_fallback(&self) -> Result<String> {
+        let mut root = self.parse()?;
+        let footnotes = footnote::extract(&mut root, self.options.footnotes(), &mut Vec::new());
+
+        let mut lines = self.fallback_lines(&root, 0);
+        for footnote in &footnotes {
+            let body = self.fallback_lines(&footnote.children, 0).join(" ");
+            lines.push(format!("{} {body}", footnote.marker));
+        }
+        let text = Self::escape(lines.join("\n").trim());
+        Ok(text::truncate(&text, self.options.fallback_chars()))
+    }
+
+    /// Converts the provided text into a `{ "blocks": [...], "text": "..." }` payload, with
+    /// the blocks of [`Self::blockify`] and the fallback of [`Self::text_fallback`]. Use
+    /// [`Self::message`] to set further fields.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::blockify`].
+    pub fn payload_stringify(&self) -> Result<String> {
+        self.message()?.to_json(Destination::Webhook)
+    }
+
+    /// Converts the provided text into a [`Message`] holding the blocks of [`Self::blockify`]
+    /// and the fallback of [`Self::text_fallback`] as its `text`, ready for the channel, thread,
+    /// and other fields of the API it is sent to.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::blockify`].
+    pub fn message(&self) -> Result<Message> {
+        Ok(Message::new(self.blockify()?).text(self.text_fallback()?))
+    }
+
+    /// Converts the provided text into a modal [`View`] titled `title`, with the blocks of
+    /// [`Self::blockify`] on [`Surface::Modal`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::blockify`].
+    pub fn modal(&self, title: impl Into<String>) -> Result<View> {
+        Ok(View::modal(title, self.clone().surface(Surface::Modal).blockify()?))
+    }
+
+    /// Converts the provided text into a Home tab [`View`], with the blocks of
+    /// [`Self::blockify`] on [`Surface::HomeTab`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::blockify`].
+    pub fn home_tab(&self) -> Result<View> {
+        Ok(View::home_tab(self.clone().surface(Surface::HomeTab).blockify()?))
+    }
+
+    /// Serializes blocks into a `{"blocks":[...]}` payload.
+    fn stringify(blocks: Vec<Block>) -> String {
+        let blocks: Vec<Value> = blocks.into_iter().map(Value::from).collect();
+        json!({ "blocks": blocks }).to_string()
+    }
+
+    /// Parses the text into the top-level nodes of a GitHub Flavored Markdown syntax tree, with
+    /// `<time>` tags collapsed and reference-style links and images resolved.
+    fn parse(&self) -> Result<Vec<Node>> {
+        match to_mdast(self.text, &ParseOptions::gfm())? {
+            Node::Root(mut root) => {
+                date::collapse(&mut root.children);
+                reference::resolve(&mut root.children);
+                Ok(root.children)
+            }
+            _ => Err(Error::Empty),
+        }
+    }
+
+    /// Parses the text for blocks, taking out the footnote definitions to be rendered in a
+    /// `context` block at the end. Markdown blocks keep footnotes as written instead.
+    fn parse_blocks(
+        &self,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<(Vec<Node>, Vec<Footnote>)> {
+        if self.mode == BlockMode::Markdown && self.surface != Surface::Message {
+            let block_type = "markdown".to_string();
+            return Err(Error::BlockNotAllowed { block_type, surface: self.surface });
+        }
+        let mut root = self.parse()?;
+        let footnotes = match self.mode {
+            BlockMode::Markdown => Vec::new(),
+            _ => footnote::extract(&mut root, self.options.footnotes(), diagnostics),
+        };
+        Ok((root, footnotes))
+    }
+
+    /// Renders a thematic break as a mrkdwn line between line breaks, as set by
+    /// [`ConvertOptions::divider`], or `None` when dividers are omitted.
+    fn divider_to_mrkdwn(&self) -> Option<String> {
+        match self.options.divider() {
+            DividerStyle::Block => Some("\n----------\n".to_string()),
+            DividerStyle::Text(text) => Some(format!("\n{}\n", Self::escape(text))),
+            DividerStyle::Omit => None,
+        }
+    }
+
+    /// Renders footnote definitions as mrkdwn lines starting with their marker.
+    fn footnote_lines(&self, footnotes: &[Footnote]) -> Vec<String> {
+        footnotes
+            .iter()
+            .map(|footnote| {
+                let body = self.transform_to_mrkdwn(&footnote.children);
+                format!("{} {}", footnote.marker, body.trim())
+            })
+            .collect()
+    }
+
+    /// Builds the `context` block holding the footnote definitions, if there are any.
+    fn footnote_block(&self, footnotes: &[Footnote]) -> Option<Located> {
+        if footnotes.is_empty() {
+            return None;
+        }
+        Some((Block::Context(vec![self.footnote_lines(footnotes).join("\n")]), None))
+    }
+
+    /// Groups top-level nodes into sections, starting a new section at every heading and
+    /// thematic break. These are the preferred places to split a document across messages.
+    fn split_sections(nodes: &[Node]) -> Vec<&[Node]> {
+        let mut sections = Vec::new();
+        let mut start = 0;
+        for (i, node) in nodes.iter().enumerate() {
+            if i > start && matches!(node, Node::Heading(_) | Node::ThematicBreak(_)) {
+                sections.push(&nodes[start..i]);
+                start = i;
+            }
+        }
+        if start < nodes.len() {
+            sections.push(&nodes[start..]);
+        }
+        sections
+    }
+
+    /// Packs sections of blocks into messages of at most the block limit, the total markdown
+    /// text limit, and the table limit, keeping each section whole unless it alone exceeds a
+    /// limit.
+    fn pack_messages(&self, sections: Vec<Vec<Located>>) -> Vec<Vec<Located>> {
+        let fits = |blocks: usize, chars: usize, tables: usize| {
+            blocks <= self.limits.blocks_on(self.surface).max
+                && chars <= self.limits.markdown_chars.max
+                && tables <= self.limits.tables.max
+        };
+        let mut messages = Vec::new();
+        let mut current = Vec::new();
+        let mut chars = 0;
+        let mut tables = 0;
+
+        for section in sections {
+            let section_chars = Self::markdown_chars(&section);
+            let section_tables = Self::tables(&section);
+            if !fits(current.len() + section.len(), chars + section_chars, tables + section_tables)
+                && !current.is_empty()
+            {
+                messages.push(std::mem::take(&mut current));
+                chars = 0;
+                tables = 0;
+            }
+            if fits(section.len(), section_chars, section_tables) {
+                current.extend(section);
+                chars += section_chars;
+                tables += section_tables;
+                continue;
+            }
+            for located in section {
+                let block_chars = Self::markdown_chars(std::slice::from_ref(&located));
+                let block_tables = Self::tables(std::slice::from_ref(&located));
+                if !fits(current.len() + 1, chars + block_chars, tables + block_tables)
+                    && !current.is_empty()
+                {
+                    messages.push(std::mem::take(&mut current));
+                    chars = 0;
+                    tables = 0;
+                }
+                current.push(located);
+                chars += block_chars;
+                tables += block_tables;
+            }
+        }
+        if !current.is_empty() {
+            messages.push(current);
+        }
+
+        messages
+    }
+
+    /// Returns the number of `table` blocks, which Slack limits per message.
+    fn tables(blocks: &[Located]) -> usize {
+        blocks
+            .iter()
+            .filter(|(block, _)| matches!(block, Block::Table { .. }))
+            .count()
+    }
+
+    /// Returns the total characters of `markdown` block text, which Slack limits per message.
+    fn markdown_chars(blocks: &[Located]) -> usize {
+        blocks
+            .iter()
+            .map(|(block, _)| match block {
+                Block::Markdown(text) => text.chars().count(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Fits each block into its limits according to their [`OverflowPolicy`], recording what
+    /// was truncated. Blocks over a limit with [`OverflowPolicy::Error`] are kept as they are
+    /// and rejected later by [`Self::validate_blocks`].
+    fn fit_blocks(&self, blocks: Vec<Located>, truncations: &mut Vec<Truncation>) -> Vec<Located> {
+        let mut fitted = Vec::new();
+        for (block, span) in blocks {
+            match block {
+                Block::Header(text) => {
+                    let limit = self.limits.header_chars;
+                    let kind = LimitKind::HeaderChars;
+                    let header = |text| (Block::Header(text), span);
+                    Self::fit_text(text, limit, kind, header, &mut fitted, truncations);
+                }
+                Block::Section(text) => {
+                    let limit = self.limits.section_chars;
+                    let kind = LimitKind::SectionChars;
+                    let section = |text| (Block::Section(text), span);
+                    Self::fit_text(text, limit, kind, section, &mut fitted, truncations);
+                }
+                Block::Markdown(text) => {
+                    let limit = self.limits.markdown_chars;
+                    let kind = LimitKind::MarkdownChars;
+                    let markdown = |text| (Block::Markdown(text), span);
+                    Self::fit_text(text, limit, kind, markdown, &mut fitted, truncations);
+                }
+                Block::Image { url, alt_text, title } => {
+                    let limit = self.limits.alt_text_chars;
+                    let actual = alt_text.chars().count();
+                    let alt_text = if actual > limit.max && limit.policy != OverflowPolicy::Error {
+                        truncations.push(Truncation {
+                            kind: LimitKind::AltTextChars,
+                            block: fitted.len(),
+                            actual,
+                            max: limit.max,
+                        });
+                        text::truncate(&alt_text, limit.max)
+                    } else {
+                        alt_text
+                    };
+                    fitted.push((Block::Image { url, alt_text, title }, span));
+                }
+                Block::Table { column_settings, rows } => {
+                    self.fit_table(column_settings, rows, span, &mut fitted, truncations);
+                }
+                Block::Context(texts) => self.fit_context(texts, span, &mut fitted, truncations),
+                block => fitted.push((block, span)),
+            }
+        }
+        fitted
+    }
+
+    /// Fits the text of a `header`, `section`, or `markdown` block into `limit`, pushing the
+    /// resulting block(s) built with `block` onto `fitted`.
+    fn fit_text(
+        text: String,
+        limit: Limit,
+        kind: LimitKind,
+        block: impl Fn(String) -> Located,
+        fitted: &mut Vec<Located>,
+        truncations: &mut Vec<Truncation>,
+    ) {
+        let actual = text.chars().count();
+        if actual <= limit.max {
+            fitted.push(block(text));
+            return;
+        }
+
+        match limit.policy {
+            OverflowPolicy::Error => fitted.push(block(text)),
+            OverflowPolicy::Truncate => {
+                truncations.push(Truncation { kind, block: fitted.len(), actual, max: limit.max });
+                fitted.push(block(text::truncate(&text, limit.max)));
+            }
+            OverflowPolicy::Split => {
+                fitted.extend(text::split(&text, limit.max).into_iter().map(block));
+            }
+        }
+    }
+
+    /// Fits a table into the column limit and then the row limit, pushing the resulting table
+    /// block(s) onto `fitted`.
+    fn fit_table(
+        &self,
+        column_settings: Vec<ColumnSettings>,
+        rows: Vec<Vec<Value>>,
+        span: Option<Span>,
+        fitted: &mut Vec<Located>,
+        truncations: &mut Vec<Truncation>,
+    ) {
+        let limit = self.limits.table_cols;
+        let actual = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let tables = match limit.policy {
+            _ if actual <= limit.max => vec![(column_settings, rows)],
+            OverflowPolicy::Error => vec![(column_settings, rows)],
+            OverflowPolicy::Truncate => {
+                truncations.push(Truncation {
+                    kind: LimitKind::TableCols,
+                    block: fitted.len(),
+                    actual,
+                    max: limit.max,
+                });
+                let keep = limit.max.saturating_sub(1);
+                let mut column_settings = column_settings;
+                column_settings.truncate(keep);
+                column_settings.push(ColumnSettings::default());
+                let rows = rows
+                    .into_iter()
+                    .map(|mut row| {
+                        row.truncate(keep);
+                        row.push(self.text_cell(text::ELLIPSIS));
+                        row
+                    })
+                    .collect();
+                vec![(column_settings, rows)]
+            }
+            OverflowPolicy::Split => (0..actual)
+                .step_by(limit.max.max(1))
+                .map(|start| {
+                    let column_settings =
+                        column_settings.iter().skip(start).take(limit.max).cloned().collect();
+                    let rows = rows
+                        .iter()
+                        .map(|row| row.iter().skip(start).take(limit.max).cloned().collect())
+                        .collect();
+                    (column_settings, rows)
+                })
+                .collect(),
+        };
+
+        for (column_settings, rows) in tables {
+            let table = |column_settings, rows| (Block::Table { column_settings, rows }, span);
+            self.fit_table_rows(column_settings, rows, table, fitted, truncations);
+        }
+    }
+
+    /// Fits a table into the row limit, pushing the resulting table block(s) built with `table`
+    /// onto `fitted`.
+    fn fit_table_rows(
+        &self,
+        column_settings: Vec<ColumnSettings>,
+        mut rows: Vec<Vec<Value>>,
+        table: impl Fn(Vec<ColumnSettings>, Vec<Vec<Value>>) -> Located,
+        fitted: &mut Vec<Located>,
+        truncations: &mut Vec<Truncation>,
+    ) {
+        let limit = self.limits.table_rows;
+        let actual = rows.len();
+        let policy = match self.options.table_fallback() {
+            TableFallback::Auto | TableFallback::SplitRows => OverflowPolicy::Split,
+            _ => limit.policy,
+        };
+        // Every split table needs the header row and one more, and truncating the `…` row, so
+        // smaller limits are left for validation to reject.
+        let policy = match policy {
+            OverflowPolicy::Split if limit.max < 2 => OverflowPolicy::Error,
+            OverflowPolicy::Truncate if limit.max == 0 => OverflowPolicy::Error,
+            policy => policy,
+        };
+        match policy {
+            _ if actual <= limit.max => fitted.push(table(column_settings, rows)),
+            OverflowPolicy::Error => fitted.push(table(column_settings, rows)),
+            OverflowPolicy::Truncate => {
+                truncations.push(Truncation {
+                    kind: LimitKind::TableRows,
+                    block: fitted.len(),
+                    actual,
+                    max: limit.max,
+                });
+                let width = rows.first().map_or(1, Vec::len);
+                rows.truncate(limit.max - 1);
+                rows.push(
+                    std::iter::once(self.text_cell(text::ELLIPSIS))
+                        .chain(std::iter::repeat_with(|| self.text_cell("")))
+                        .take(width)
+                        .collect(),
+                );
+                fitted.push(table(column_settings, rows));
+            }
+            OverflowPolicy::Split => {
+                // The first row is the Markdown header row, repeated at the top of every table.
+                let header = rows.remove(0);
+                for chunk in rows.chunks(limit.max - 1) {
+                    let mut rows = vec![header.clone()];
+                    rows.extend_from_slice(chunk);
+                    fitted.push(table(column_settings.clone(), rows));
+                }
+            }
+        }
+    }
+
+    /// Fits the text of each element of a `context` block into the character limit, and then
+    /// the elements into the element limit, pushing the resulting context block(s) onto
+    /// `fitted`.
+    fn fit_context(
+        &self,
+        texts: Vec<String>,
+        span: Option<Span>,
+        fitted: &mut Vec<Located>,
+        truncations: &mut Vec<Truncation>,
+    ) {
+        let limit = self.limits.context_chars;
+        let mut elements = Vec::new();
+        for text in texts {
+            let actual = text.chars().count();
+            match limit.policy {
+                _ if actual <= limit.max => elements.push(text),
+                OverflowPolicy::Error => elements.push(text),
+                OverflowPolicy::Truncate => {
+                    truncations.push(Truncation {
+                        kind: LimitKind::ContextChars,
+                        block: fitted.len(),
+                        actual,
+                        max: limit.max,
+                    });
+                    elements.push(text::truncate(&text, limit.max));
+                }
+                OverflowPolicy::Split => elements.extend(text::split(&text, limit.max)),
+            }
+        }
+
+        let limit = self.limits.context_elements;
+        let actual = elements.len();
+        match limit.policy {
+            // Without room for a single element, there is nothing to truncate or split into.
+            _ if actual <= limit.max || limit.max == 0 => {
+                fitted.push((Block::Context(elements), span));
+            }
+            OverflowPolicy::Error => fitted.push((Block::Context(elements), span)),
+            OverflowPolicy::Truncate => {
+                truncations.push(Truncation {
+                    kind: LimitKind::ContextElements,
+                    block: fitted.len(),
+                    actual,
+                    max: limit.max,
+                });
+                elements.truncate(limit.max - 1);
+                elements.push(text::ELLIPSIS.to_string());
+                fitted.push((Block::Context(elements), span));
+            }
+            OverflowPolicy::Split => {
+                for chunk in elements.chunks(limit.max) {
+                    fitted.push((Block::Context(chunk.to_vec()), span));
+                }
+            }
+        }
+    }
+
+    /// Fits the total markdown text into its limit. Only [`OverflowPolicy::Truncate`] is
+    /// applied: the block crossing the limit is cut, and every block after it is dropped.
+    fn fit_markdown_chars(
+        &self,
+        mut blocks: Vec<Located>,
+        truncations: &mut Vec<Truncation>,
+    ) -> Vec<Located> {
+        let limit = self.limits.markdown_chars;
+        let actual = Self::markdown_chars(&blocks);
+        if actual <= limit.max || limit.policy != OverflowPolicy::Truncate {
+            return blocks;
+        }
 
-        let root = ast.children().ok_or_else(|| anyhow!("no input?"))?;
+        let mut total = 0;
+        for i in 0..blocks.len() {
+            let Block::Markdown(text) = &blocks[i].0 else { continue };
+            let len = text.chars().count();
+            if total + len > limit.max {
+                let remaining = limit.max - total;
+                if remaining == 0 {
+                    blocks.truncate(i);
+                } else {
+                    blocks[i].0 = Block::Markdown(text::truncate(text, remaining));
+                    blocks.truncate(i + 1);
+                }
+                let block = i.min(blocks.len().saturating_sub(1));
+                // The cut supersedes any truncation of the same block to the per-block limit.
+                truncations.retain(|truncation| {
+                    truncation.block < block
+                        || (truncation.block == block
+                            && truncation.kind != LimitKind::MarkdownChars)
+                });
+                truncations.push(Truncation {
+                    kind: LimitKind::MarkdownChars,
+                    block,
+                    actual,
+                    max: limit.max,
+                });
+                break;
+            }
+            total += len;
+        }
+        blocks
+    }
 
-        let blocks = self.transform_to_blocks(root)?;
-        Self::validate_blocks(&blocks)?;
-        Ok(blocks)
+    /// Fits the number of blocks into the block limit. Only [`OverflowPolicy::Truncate`] is
+    /// applied: the trailing blocks are replaced by a single `…` section.
+    fn fit_block_count(
+        &self,
+        mut blocks: Vec<Located>,
+        truncations: &mut Vec<Truncation>,
+    ) -> Vec<Located> {
+        let limit = self.limits.blocks_on(self.surface);
+        let actual = blocks.len();
+        if actual > limit.max && limit.policy == OverflowPolicy::Truncate {
+            let keep = limit.max.saturating_sub(1);
+            let span = blocks[keep].1;
+            blocks.truncate(keep);
+            blocks.push((Block::Section(text::ELLIPSIS.to_string()), span));
+            truncations.retain(|truncation| truncation.block < keep);
+            truncations.push(Truncation {
+                kind: LimitKind::Blocks,
+                block: keep,
+                actual,
+                max: limit.max,
+            });
+        }
+        blocks
     }
 
-    /// Checks the converted blocks against the limits Slack enforces, so an over-limit message
+    /// Checks the converted blocks against the configured limits, so an over-limit message
     /// fails here instead of being rejected by the Slack API.
-    fn validate_blocks(blocks: &[Block]) -> Result<()> {
-        if blocks.len() > MAX_BLOCKS {
-            return Err(anyhow!("message has {} blocks, Slack allows {MAX_BLOCKS}", blocks.len()));
+    fn validate_blocks(&self, blocks: &[Located]) -> Result<()> {
+        match self.violations(blocks).into_iter().next() {
+            Some(violation) => Err(violation.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Collects every violation of the configured limits in the converted blocks, in block
+    /// order. The block count and the total markdown text come first since they concern the
+    /// whole message.
+    fn violations(&self, blocks: &[Located]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut check = |kind: LimitKind, block: usize, actual: usize, span: Option<Span>| {
+            let max = self.limits.get(kind, self.surface).max;
+            if actual > max {
+                violations.push(Violation { kind, block, actual, max, span });
+            }
+        };
+
+        let max_blocks = self.limits.blocks_on(self.surface).max;
+        if let Some((_, span)) = blocks.get(max_blocks) {
+            check(LimitKind::Blocks, max_blocks, blocks.len(), *span);
         }
 
-        for block in blocks {
+        // The markdown limit is cumulative, so it is checked apart from the per-block limits.
+        let max_markdown_chars = self.limits.markdown_chars.max;
+        let mut total = 0;
+        let mut first_over = None;
+        for (i, (block, span)) in blocks.iter().enumerate() {
+            if let Block::Markdown(text) = block {
+                total += text.chars().count();
+                if total > max_markdown_chars && first_over.is_none() {
+                    first_over = Some((i, *span));
+                }
+            }
+        }
+        if let Some((i, span)) = first_over {
+            check(LimitKind::MarkdownChars, i, total, span);
+        }
+
+        let tables: Vec<_> = blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, (block, _))| matches!(block, Block::Table { .. }))
+            .collect();
+        if let Some((i, (_, span))) = tables.get(self.limits.tables.max) {
+            check(LimitKind::Tables, *i, tables.len(), *span);
+        }
+
+        for (i, (block, span)) in blocks.iter().enumerate() {
+            let span = *span;
             match block {
-                Block::Header(text) if text.chars().count() > MAX_HEADER_CHARS => {
-                    return Err(anyhow!(
-                        "header text has {} characters, Slack allows {MAX_HEADER_CHARS}",
-                        text.chars().count()
-                    ));
-                }
-                Block::Section(text) if text.chars().count() > MAX_SECTION_CHARS => {
-                    return Err(anyhow!(
-                        "section text has {} characters, Slack allows {MAX_SECTION_CHARS}",
-                        text.chars().count()
-                    ));
-                }
-                Block::Image { alt_text, .. } if alt_text.chars().count() > MAX_ALT_TEXT_CHARS => {
-                    return Err(anyhow!(
-                        "image alt_text has {} characters, Slack allows {MAX_ALT_TEXT_CHARS}",
-                        alt_text.chars().count()
-                    ));
+                Block::Header(text) => check(LimitKind::HeaderChars, i, text.chars().count(), span),
+                Block::Section(text) => {
+                    check(LimitKind::SectionChars, i, text.chars().count(), span);
+                }
+                Block::Image { alt_text, .. } => {
+                    check(LimitKind::AltTextChars, i, alt_text.chars().count(), span);
                 }
                 Block::Table { rows, .. } => {
-                    if rows.len() > MAX_TABLE_ROWS {
-                        return Err(anyhow!(
-                            "table has {} rows, Slack allows {MAX_TABLE_ROWS}",
-                            rows.len()
-                        ));
-                    }
-                    if let Some(row) = rows.iter().find(|row| row.len() > MAX_TABLE_COLS) {
-                        return Err(anyhow!(
-                            "table has {} columns, Slack allows {MAX_TABLE_COLS}",
-                            row.len()
-                        ));
+                    check(LimitKind::TableRows, i, rows.len(), span);
+                    let cols = rows.iter().map(Vec::len).max().unwrap_or_default();
+                    check(LimitKind::TableCols, i, cols, span);
+                }
+                Block::Context(texts) => {
+                    check(LimitKind::ContextElements, i, texts.len(), span);
+                    for text in texts {
+                        check(LimitKind::ContextChars, i, text.chars().count(), span);
                     }
                 }
-                _ => {}
+                Block::Divider | Block::RichText(_) | Block::Markdown(_) => {}
             }
         }
 
-        Ok(())
+        violations
     }
 
     fn transform_to_mrkdwn(&self, nodes: &[Node]) -> String {
@@ -164,32 +977,24 @@ impl<'a> Mrkdwn<'a> {
         nodes
             .iter()
             .map(|child| match child {
-                Node::Blockquote(n) => {
-                    self.surround_nodes_with(&n.children, "> ", "", indent_level)
-                }
+                Node::Blockquote(n) => self.handle_blockquote(&n.children, indent_level),
                 Node::Break(_) => "\n".to_string(),
                 Node::Code(n) => Self::surround_with(&Self::escape(&n.value), "```\n", "\n```\n"),
                 Node::Delete(n) => self.surround_nodes_with(&n.children, "~", "~", indent_level),
                 Node::Emphasis(n) => self.surround_nodes_with(&n.children, "_", "_", indent_level),
-                Node::FootnoteReference(n) => {
-                    format!("[^{}]", Self::footnote_label(&n.identifier, &n.label))
-                }
-                Node::FootnoteDefinition(n) => {
-                    let body = self.transform_to_mrkdwn_with_indent(&n.children, indent_level);
-                    format!(
-                        "[^{}]: {}\n",
-                        Self::footnote_label(&n.identifier, &n.label),
-                        body.trim()
-                    )
-                }
                 Node::Heading(n) => {
                     self.surround_nodes_with(&n.children, "*", "*\n\n", indent_level)
                 }
-                Node::Image(n) => Self::image_to_link(n),
+                Node::Html(n) => Date::from_html(&n.value)
+                    .map(|date| date.to_mrkdwn())
+                    .unwrap_or_default(),
+                Node::Image(n) => self.image_to_mrkdwn(n),
                 Node::InlineCode(n) => Self::surround_with(&Self::escape(&n.value), "`", "`"),
                 Node::Link(n) => Self::mrkdwn_link(
                     &n.url,
-                    &self.transform_to_mrkdwn_with_indent(&n.children, indent_level),
+                    &self
+                        .without_mentions()
+                        .transform_to_mrkdwn_with_indent(&n.children, indent_level),
                 ),
                 Node::List(n) => self.handle_list(n, indent_level),
                 Node::ListItem(n) => {
@@ -198,65 +1003,438 @@ impl<'a> Mrkdwn<'a> {
                 Node::Paragraph(n) => self.surround_nodes_with(&n.children, "", "\n", indent_level),
                 Node::Strong(n) => self.surround_nodes_with(&n.children, "*", "*", indent_level),
                 Node::Table(n) => self.table_to_text(n, indent_level),
-                Node::Text(n) => Self::escape(&n.value),
-                Node::ThematicBreak(_) => "\n----------\n".to_string(),
+                Node::Text(n) => self.text_to_mrkdwn(&n.value),
+                Node::ThematicBreak(_) => self.divider_to_mrkdwn().unwrap_or_default(),
                 _ => String::new(),
             })
             .collect()
     }
 
-    fn transform_to_blocks(&self, nodes: &[Node]) -> Result<Vec<Block>> {
-        use crate::block::Block::*;
+    fn transform_to_blocks(&self, nodes: &[Node]) -> Vec<Located> {
+        if self.mode == BlockMode::Markdown {
+            return self.transform_to_markdown_blocks(nodes);
+        }
 
-        Ok(nodes
+        let mut tables = 0;
+        nodes
             .iter()
-            .flat_map(|child| match child {
-                Node::Blockquote(n) => {
-                    vec![Section(self.surround_nodes_with(&n.children, "> ", "", 0))]
-                }
-                Node::Break(_) => vec![Section("\n".to_string())],
-                Node::Code(n) => {
-                    vec![Section(Self::surround_with(&Self::escape(&n.value), "```\n", "\n```\n"))]
+            .flat_map(|child| {
+                let span = child.position().map(Span::from);
+                let blocks = match child {
+                    Node::Table(table) if self.table_as_text(table, &mut tables) => {
+                        vec![Block::Section(self.table_to_text(table, 0))]
+                    }
+                    _ => self.node_to_blocks(child),
+                };
+                blocks.into_iter().map(move |block| (block, span))
+            })
+            .collect()
+    }
+
+    /// Counts the table blocks `table` is fitted into among those of the nodes being converted,
+    /// and returns whether they would go beyond the table limit with
+    /// [`OverflowPolicy::Truncate`], or are on a surface without table blocks, so the table is
+    /// rendered as text instead.
+    fn table_as_text(&self, table: &Table, tables: &mut usize) -> bool {
+        if self.surface != Surface::Message {
+            return true;
+        }
+        if !matches!(self.table_fallback(table), TableFallback::Off | TableFallback::SplitRows) {
+            return false;
+        }
+        let pieces = self.table_pieces(table);
+        if *tables + pieces > self.limits.tables.max
+            && self.limits.tables.policy == OverflowPolicy::Truncate
+        {
+            return true;
+        }
+        *tables += pieces;
+        false
+    }
+
+    /// Returns how many table blocks `table` becomes once fitted into the row and column
+    /// limits, which split it into several under [`OverflowPolicy::Split`].
+    fn table_pieces(&self, table: &Table) -> usize {
+        let blocks = self
+            .table_blocks(table)
+            .into_iter()
+            .map(|block| (block, None))
+            .collect();
+        Self::tables(&self.fit_blocks(blocks, &mut Vec::new()))
+    }
+
+    /// Converts top-level nodes into `markdown` blocks for [`BlockMode::Markdown`], each holding
+    /// consecutive nodes rendered as normalized GFM. Tables and images in paragraphs are
+    /// converted to table and image blocks, which end the current markdown block.
+    fn transform_to_markdown_blocks(&self, nodes: &[Node]) -> Vec<Located> {
+        let mut blocks = Vec::new();
+        // The nodes of the current markdown block, and the source they span.
+        let mut pending: Vec<Node> = Vec::new();
+        let mut source: Option<unist::Position> = None;
+        let flush = |pending: &mut Vec<Node>,
+                     source: &mut Option<unist::Position>,
+                     blocks: &mut Vec<Located>| {
+            let text = gfm::nodes_to_markdown(&std::mem::take(pending));
+            if let Some(position) = source.take()
+                && !text.trim().is_empty()
+            {
+                let text = text.trim().to_string();
+                blocks.push((Block::Markdown(text), Some(Span::from(&position))));
+            }
+        };
+        let extend = |source: &mut Option<unist::Position>, position: &unist::Position| {
+            *source = Some(match source.take() {
+                Some(source) => unist::Position { end: position.end.clone(), ..source },
+                None => position.clone(),
+            });
+        };
+
+        let mut tables = 0;
+        for node in nodes {
+            let Some(position) = node.position() else { continue };
+            match node {
+                Node::Table(table) if self.table_as_text(table, &mut tables) => {
+                    flush(&mut pending, &mut source, &mut blocks);
+                    let text = self.table_to_text(table, 0);
+                    blocks.push((Block::Section(text), Some(Span::from(position))));
                 }
-                Node::Delete(n) => {
-                    vec![Section(self.surround_nodes_with(&n.children, "~", "~", 0))]
+                Node::Table(table) => {
+                    flush(&mut pending, &mut source, &mut blocks);
+                    let span = Some(Span::from(position));
+                    blocks.extend(self.table_blocks(table).into_iter().map(|block| (block, span)));
                 }
-                Node::Emphasis(n) => {
-                    vec![Section(self.surround_nodes_with(&n.children, "_", "_", 0))]
-                }
-                Node::FootnoteDefinition(n) => {
-                    let body = self.transform_to_mrkdwn(&n.children);
-                    vec![Section(format!(
-                        "[^{}]: {}\n",
-                        Self::footnote_label(&n.identifier, &n.label),
-                        body.trim()
-                    ))]
-                }
-                Node::Heading(n) => match n.depth {
-                    // `header` blocks render as `plain_text`, so use the unformatted text and
-                    // drop any inline markup rather than leaking literal `*`/`_` characters.
-                    1 => vec![Header(Self::plain_text(&n.children)), Divider],
-                    2 => vec![Header(Self::plain_text(&n.children))],
-                    _ => vec![Section(self.surround_nodes_with(&n.children, "*", "*", 0))],
-                },
-                Node::InlineCode(n) => {
-                    vec![Section(Self::surround_with(&Self::escape(&n.value), "`", "`"))]
+                Node::Paragraph(paragraph)
+                    if self.options.images() == ImageStyle::Block
+                        && paragraph.children.iter().any(|node| matches!(node, Node::Image(_))) =>
+                {
+                    // The text around the images is kept as paragraphs of its own.
+                    let mut inline = Vec::new();
+                    let text_run =
+                        |children| Node::Paragraph(Paragraph { children, position: None });
+                    for child in &paragraph.children {
+                        let Some(position) = child.position() else { continue };
+                        if let Node::Image(image) = child {
+                            if !inline.is_empty() {
+                                pending.push(text_run(std::mem::take(&mut inline)));
+                            }
+                            flush(&mut pending, &mut source, &mut blocks);
+                            blocks.push((Self::image_block(image), Some(Span::from(position))));
+                        } else {
+                            extend(&mut source, position);
+                            inline.push(child.clone());
+                        }
+                    }
+                    if !inline.is_empty() {
+                        pending.push(text_run(inline));
+                    }
                 }
-                Node::Link(n) => {
-                    vec![Section(Self::mrkdwn_link(&n.url, &self.transform_to_mrkdwn(&n.children)))]
+                _ => {
+                    extend(&mut source, position);
+                    pending.push(node.clone());
                 }
-                Node::List(n) => vec![Section(self.handle_list(n, 0))],
-                Node::ListItem(n) => vec![Section(self.transform_to_mrkdwn(&n.children))],
-                Node::Paragraph(n) => self.handle_paragraph(&n.children),
-                Node::Strong(n) => {
+            }
+        }
+        flush(&mut pending, &mut source, &mut blocks);
+
+        blocks
+    }
+
+    fn node_to_blocks(&self, node: &Node) -> Vec<Block> {
+        use crate::block::Block::*;
+
+        if self.mode == BlockMode::RichText
+            && let Some(blocks) = self.node_to_rich_text(node)
+        {
+            return blocks;
+        }
+
+        match node {
+            Node::Blockquote(n) => vec![Section(self.handle_blockquote(&n.children, 0))],
+            Node::Break(_) => vec![Section("\n".to_string())],
+            Node::Code(n) => {
+                vec![Section(Self::surround_with(&Self::escape(&n.value), "```\n", "\n```\n"))]
+            }
+            Node::Delete(n) => {
+                vec![Section(self.surround_nodes_with(&n.children, "~", "~", 0))]
+            }
+            Node::Emphasis(n) => {
+                vec![Section(self.surround_nodes_with(&n.children, "_", "_", 0))]
+            }
+            Node::Heading(n) => match self.options.heading(n.depth) {
+                // `header` blocks render as `plain_text`, so use the unformatted text and
+                // drop any inline markup rather than leaking literal `*`/`_` characters.
+                HeadingStyle::HeaderWithDivider => vec![Header(self.header_text(n)), Divider],
+                HeadingStyle::Header => vec![Header(self.header_text(n))],
+                HeadingStyle::Bold => {
                     vec![Section(self.surround_nodes_with(&n.children, "*", "*", 0))]
                 }
-                Node::Table(n) => vec![Self::handle_table(n)],
-                Node::Text(n) => vec![Section(Self::escape(&n.value))],
-                Node::ThematicBreak(_) => vec![Divider],
-                _ => vec![],
+            },
+            Node::InlineCode(n) => {
+                vec![Section(Self::surround_with(&Self::escape(&n.value), "`", "`"))]
+            }
+            Node::Link(n) => {
+                let text = self.without_mentions().transform_to_mrkdwn(&n.children);
+                vec![Section(Self::mrkdwn_link(&n.url, &text))]
+            }
+            Node::List(n) => vec![Section(self.handle_list(n, 0))],
+            Node::ListItem(n) => vec![Section(self.transform_to_mrkdwn(&n.children))],
+            Node::Paragraph(n) => self.handle_paragraph(&n.children),
+            Node::Strong(n) => {
+                vec![Section(self.surround_nodes_with(&n.children, "*", "*", 0))]
+            }
+            Node::Table(n) => self.table_blocks(n),
+            Node::Text(n) => vec![Section(self.text_to_mrkdwn(&n.value))],
+            Node::ThematicBreak(_) => match self.options.divider() {
+                DividerStyle::Block => vec![Divider],
+                DividerStyle::Text(text) => vec![Section(format!("{}\n", Self::escape(text)))],
+                DividerStyle::Omit => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Converts a node into `rich_text` blocks for [`BlockMode::RichText`], or returns `None` for
+    /// nodes whose blocks are the same in both modes.
+    fn node_to_rich_text(&self, node: &Node) -> Option<Vec<Block>> {
+        let rich_text = |element: Value| vec![Block::RichText(vec![element])];
+
+        match node {
+            Node::Blockquote(n) => Some(rich_text(json!({
+                "type": "rich_text_quote",
+                "elements": Self::non_empty(self.block_elements(&n.children, Style::default())),
+            }))),
+            Node::Code(n) => Some(rich_text(json!({
+                "type": "rich_text_preformatted",
+                "elements": [Self::text_element(&n.value, Style::default())],
+            }))),
+            Node::Heading(n) if self.options.heading(n.depth) == HeadingStyle::Bold => {
+                Some(rich_text(Self::rich_text_section(
+                    self.rich_text_elements(&n.children, Style::default().bold()),
+                )))
+            }
+            Node::List(n) => {
+                let mut lists = Vec::new();
+                self.rich_text_list(n, 0, &mut lists);
+                Some(vec![Block::RichText(lists)])
+            }
+            Node::Paragraph(n) => {
+                let mut blocks = Vec::new();
+                let mut elements = Vec::new();
+                for node in &n.children {
+                    match node {
+                        Node::Image(image) if self.options.images() == ImageStyle::Block => {
+                            if !elements.is_empty() {
+                                let elements = std::mem::take(&mut elements);
+                                blocks.extend(rich_text(Self::rich_text_section(elements)));
+                            }
+                            blocks.push(Self::image_block(image));
+                        }
+                        node => elements.extend(
+                            self.rich_text_elements(std::slice::from_ref(node), Style::default()),
+                        ),
+                    }
+                }
+                if !elements.is_empty() {
+                    blocks.extend(rich_text(Self::rich_text_section(elements)));
+                }
+                Some(blocks)
+            }
+            _ => None,
+        }
+    }
+
+    /// Appends a list as `rich_text_list` elements. Slack lists cannot contain other blocks, so
+    /// a nested list ends the current element and continues at `indent + 1`, and the rest of
+    /// the outer list continues in a new element whose `offset` keeps its numbering.
+    fn rich_text_list(&self, list: &List, indent: usize, lists: &mut Vec<Value>) {
+        let style = if list.ordered { "ordered" } else { "bullet" };
+        let mut offset = list.start.unwrap_or(1).saturating_sub(1) as usize;
+        let mut items = Vec::new();
+        let mut flush = |items: &mut Vec<Value>, lists: &mut Vec<Value>| {
+            if items.is_empty() {
+                return;
+            }
+            let mut element = json!({
+                "type": "rich_text_list",
+                "style": style,
+                "indent": indent,
+                "elements": std::mem::take(items),
+            });
+            if list.ordered && offset > 0 {
+                element["offset"] = json!(offset);
+            }
+            offset += element["elements"].as_array().map_or(0, Vec::len);
+            lists.push(element);
+        };
+
+        for item in &list.children {
+            let Node::ListItem(item) = item else { continue };
+            let mut elements = match item.checked {
+                Some(checked) => vec![Self::text_element(
+                    &format!("{} ", self.options.checkbox(checked)),
+                    Style::default(),
+                )],
+                None => Vec::new(),
+            };
+            let mut pushed = false;
+            let mut continued = false;
+            for child in &item.children {
+                if let Node::List(nested) = child {
+                    // Content after a nested list cannot rejoin its item, so it gets an item of
+                    // its own.
+                    if !pushed || !elements.is_empty() {
+                        items.push(Self::rich_text_section(Self::non_empty(std::mem::take(
+                            &mut elements,
+                        ))));
+                        pushed = true;
+                        continued = false;
+                    }
+                    flush(&mut items, lists);
+                    self.rich_text_list(nested, indent + 1, lists);
+                } else {
+                    let line = self.block_elements(std::slice::from_ref(child), Style::default());
+                    if continued && !line.is_empty() {
+                        elements.push(Self::text_element("\n", Style::default()));
+                    }
+                    continued |= !line.is_empty();
+                    elements.extend(line);
+                }
+            }
+            if !pushed || !elements.is_empty() {
+                items.push(Self::rich_text_section(Self::non_empty(elements)));
+            }
+        }
+        flush(&mut items, lists);
+    }
+
+    /// Flattens block-level nodes into inline `rich_text` elements, one line per block, for
+    /// places that only take inline content: list items and quotes.
+    fn block_elements(&self, nodes: &[Node], style: Style) -> Vec<Value> {
+        let mut elements = Vec::new();
+        for node in nodes {
+            let line = match node {
+                Node::Blockquote(n) => self.block_elements(&n.children, style),
+                Node::Code(n) => vec![Self::text_element(&n.value, style.code())],
+                Node::Heading(n) => self.rich_text_elements(&n.children, style.bold()),
+                Node::List(n) => n
+                    .children
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, item)| {
+                        let marker = if n.ordered {
+                            format!("{}. ", n.start.unwrap_or(1) as usize + i)
+                        } else {
+                            format!("{} ", self.options.bullet(0))
+                        };
+                        let mut line = vec![Self::text_element(&marker, style)];
+                        line.extend(self.block_elements(
+                            item.children().map_or(&[][..], |v| v.as_slice()),
+                            style,
+                        ));
+                        if i + 1 < n.children.len() {
+                            line.push(Self::text_element("\n", style));
+                        }
+                        line
+                    })
+                    .collect(),
+                Node::Paragraph(n) => self.rich_text_elements(&n.children, style),
+                _ => Vec::new(),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            if !elements.is_empty() {
+                elements.push(Self::text_element("\n", style));
+            }
+            elements.extend(line);
+        }
+        elements
+    }
+
+    /// Slack rejects `rich_text` containers with no elements, so fill an empty one with an
+    /// empty text element.
+    fn non_empty(mut elements: Vec<Value>) -> Vec<Value> {
+        if elements.is_empty() {
+            elements.push(Self::text_element("", Style::default()));
+        }
+        elements
+    }
+
+    /// Cuts literal text into the mentions the resolver knows, when there is one.
+    fn mention_pieces<'t>(&self, text: &'t str) -> Vec<mention::Piece<'t>> {
+        match self.mentions {
+            Some(resolver) => mention::split(text, resolver),
+            None => vec![mention::Piece::Text(text)],
+        }
+    }
+
+    /// Escapes literal text, turning the handles the mention resolver knows into mentions and
+    /// writing emoji according to the options.
+    fn text_to_mrkdwn(&self, text: &str) -> String {
+        let mut result = String::new();
+        for piece in self.mention_pieces(text) {
+            match piece {
+                mention::Piece::Text(text) => {
+                    for piece in emoji::split(text, self.options.emoji()) {
+                        match piece {
+                            emoji::Piece::Text(text) => result.push_str(&Self::escape(text)),
+                            emoji::Piece::Emoji(emoji) => result.push_str(&emoji.to_mrkdwn()),
+                        }
+                    }
+                }
+                mention::Piece::Mention(mention) => result.push_str(&mention.to_mrkdwn()),
+            }
+        }
+        result
+    }
+
+    /// Builds `rich_text` elements for literal text: `text` elements, with `user`, `channel`,
+    /// `usergroup`, and `broadcast` elements for mentions and `emoji` elements for shortcodes.
+    fn text_elements(&self, text: &str, style: Style) -> Vec<Value> {
+        let styled = |mut element: Value| {
+            if let Some(value) = style.to_value() {
+                element["style"] = value;
+            }
+            element
+        };
+        let mut elements = Vec::new();
+        for piece in self.mention_pieces(text) {
+            match piece {
+                mention::Piece::Text(text) => {
+                    for piece in emoji::split(text, self.options.emoji()) {
+                        match piece {
+                            emoji::Piece::Text(text) => {
+                                elements.push(Self::text_element(text, style));
+                            }
+                            emoji::Piece::Emoji(emoji) => elements.push(match emoji.to_element() {
+                                Some(element) => element,
+                                None => Self::text_element(&emoji.to_mrkdwn(), style),
+                            }),
+                        }
+                    }
+                }
+                mention::Piece::Mention(mention) => elements.push(styled(mention.to_element())),
+            }
+        }
+        elements
+    }
+
+    /// Returns the `plain_text` of a `header` block, with emoji written like in other text.
+    /// Slack renders shortcodes in it since header blocks set `"emoji": true`.
+    fn header_text(&self, heading: &Heading) -> String {
+        emoji::split(&Self::plain_text(&heading.children), self.options.emoji())
+            .into_iter()
+            .map(|piece| match piece {
+                emoji::Piece::Text(text) => text.to_string(),
+                emoji::Piece::Emoji(emoji) => emoji.to_mrkdwn(),
             })
-            .collect())
+            .collect()
+    }
+
+    /// Returns a copy of this converter that leaves handles as text, for link text where a
+    /// nested `<@U…>` would break the `<url|text>` syntax.
+    fn without_mentions(&self) -> Mrkdwn<'a> {
+        Mrkdwn { mentions: None, ..self.clone() }
     }
 
     fn surround_with(s: &str, prefix: &str, suffix: &str) -> String {
@@ -284,7 +1462,7 @@ impl<'a> Mrkdwn<'a> {
     /// this crate generates itself (link delimiters, blockquote/list markers) and URLs are left
     /// untouched so they keep their special meaning. `&` is escaped first to avoid
     /// double-escaping the entities produced for `<` and `>`.
-    fn escape(text: &str) -> String {
+    pub(crate) fn escape(text: &str) -> String {
         text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
     }
 
@@ -298,54 +1476,224 @@ impl<'a> Mrkdwn<'a> {
         format!("{prefix}{}{suffix}", self.transform_to_mrkdwn_with_indent(nodes, indent_level))
     }
 
+    /// Renders a blockquote by prefixing every line of its content with `> `, so paragraphs,
+    /// lists, code blocks, and nested quotes after the first line stay quoted. Quoted blocks
+    /// follow each other on the next line, as they do outside a quote; only blank lines the
+    /// content itself ends with, such as the one after a list, become a bare `>`.
+    fn handle_blockquote(&self, nodes: &[Node], indent_level: usize) -> String {
+        let body = self.transform_to_mrkdwn_with_indent(nodes, indent_level);
+        let lines: Vec<String> = body
+            .trim_end_matches('\n')
+            .split('\n')
+            .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {line}") })
+            .collect();
+        format!("{}\n", lines.join("\n"))
+    }
+
+    /// Renders a list as mrkdwn lines, nested lists indented by [`ConvertOptions::indent_width`]
+    /// per level.
+    ///
+    /// Ordered lists count from their start number. The blocks of an item after its first one,
+    /// such as continuation paragraphs and code blocks, are indented under the item's text. Loose
+    /// lists (items separated by blank lines in the Markdown) keep a blank line between items
+    /// and between the blocks of an item.
     fn handle_list(&self, list: &List, indent_level: usize) -> String {
-        let indent = "    ".repeat(indent_level);
-        list.children
+        let indent = " ".repeat(self.options.indent_width() * indent_level);
+        let content_indent = " ".repeat(self.options.indent_width() * (indent_level + 1));
+        let start = list.start.unwrap_or(1);
+        let items: Vec<&ListItem> = list
+            .children
             .iter()
-            .enumerate()
-            .fold(String::new(), |acc, (i, item)| {
-                let prefix = if list.ordered {
-                    format!("{}.  ", i + 1)
-                } else {
-                    let checked = list
-                        .children
-                        .get(i)
-                        .and_then(|n| if let Node::ListItem(li) = n { li.checked } else { None });
-                    format!(
-                        "{}   ",
-                        match checked {
-                            None => "•",
-                            Some(true) => "\u{2611}",
-                            Some(false) => "\u{2610}",
-                        }
-                    )
+            .filter_map(|node| if let Node::ListItem(item) = node { Some(item) } else { None })
+            .collect();
+        // The parser also marks the last item spread when blank lines follow the list, so check
+        // that the item's own blocks are separated by one.
+        let spread = |item: &ListItem| {
+            item.spread
+                && item.children.windows(2).any(|pair| {
+                    match (pair[0].position(), pair[1].position()) {
+                        (Some(previous), Some(next)) => next.start.line > previous.end.line + 1,
+                        _ => false,
+                    }
+                })
+        };
+        let loose = list.spread || items.iter().any(|item| spread(item));
+
+        let mut result = String::new();
+        for (i, item) in items.iter().enumerate() {
+            if loose && i > 0 {
+                result.push('\n');
+            }
+            let marker = if list.ordered {
+                let number = start.saturating_add(i as u32);
+                format!("{}.  ", self.options.numbers().format(number, indent_level))
+            } else {
+                let glyph = match item.checked {
+                    None => self.options.bullet(indent_level),
+                    Some(checked) => self.options.checkbox(checked),
                 };
+                format!("{}   ", Self::escape(glyph))
+            };
+            result.push_str(&indent);
+            result.push_str(&marker);
+
+            let mut first = true;
+            for child in &item.children {
+                let text = match child {
+                    Node::List(n) => self.handle_list(n, indent_level + 1),
+                    _ => self.transform_to_mrkdwn_with_indent(
+                        std::slice::from_ref(child),
+                        indent_level + 1,
+                    ),
+                };
+                let text = text.trim_end_matches('\n');
+                if !first {
+                    result.push_str(if loose { "\n\n" } else { "\n" });
+                }
+                for (j, line) in text.split('\n').enumerate() {
+                    if j > 0 {
+                        result.push('\n');
+                    }
+                    // Nested lists indent their own lines; other blocks are indented under the
+                    // item's text, except for the line that follows the marker.
+                    let after_marker = first && j == 0;
+                    if !after_marker && !matches!(child, Node::List(_)) && !line.is_empty() {
+                        result.push_str(&content_indent);
+                    }
+                    result.push_str(line);
+                }
+                first = false;
+            }
+            result.push('\n');
+        }
+        result + "\n"
+    }
+
+    /// Converts a Markdown table into blocks: a table block, or for a table beyond the table
+    /// limits, the section that [`ConvertOptions::table_fallback`] asks for. Splitting the rows
+    /// happens later, when the blocks are fitted into the limits.
+    fn table_blocks(&self, table: &Table) -> Vec<Block> {
+        match self.table_fallback(table) {
+            TableFallback::Code => vec![Block::Section(Self::table_to_monospace(table))],
+            TableFallback::Records => vec![Block::Section(self.table_to_records(table))],
+            TableFallback::Off | TableFallback::Auto | TableFallback::SplitRows => {
+                vec![self.handle_table(table)]
+            }
+        }
+    }
+
+    /// Returns the fallback `table` needs: [`TableFallback::Off`] when it fits in a table block,
+    /// and otherwise the configured one, with [`TableFallback::Auto`] resolved.
+    fn table_fallback(&self, table: &Table) -> TableFallback {
+        let rows = table.children.len();
+        let columns = table
+            .children
+            .iter()
+            .filter_map(Node::children)
+            .map(Vec::len)
+            .max()
+            .unwrap_or_default();
+        let too_many_columns = columns > self.limits.table_cols.max;
+        if rows <= self.limits.table_rows.max && !too_many_columns {
+            return TableFallback::Off;
+        }
+
+        match self.options.table_fallback() {
+            TableFallback::Auto
+                if !too_many_columns && self.limits.tables.policy == OverflowPolicy::Split =>
+            {
+                TableFallback::SplitRows
+            }
+            TableFallback::Auto
+                if Self::table_to_monospace(table).chars().count()
+                    <= self.limits.section_chars.max =>
+            {
+                TableFallback::Code
+            }
+            TableFallback::Auto => TableFallback::Records,
+            fallback => fallback,
+        }
+    }
+
+    /// Renders a table as a line of mrkdwn per row below the header row, labeling every
+    /// non-empty cell with the header of its column: `*Name:* Alice, *Score:* 10`. A table with
+    /// only a header row renders it as a single record of labels.
+    fn table_to_records(&self, table: &Table) -> String {
+        let headers: Vec<String> = Self::table_headers(table)
+            .iter()
+            .map(|header| Self::escape(header))
+            .collect();
 
-                let children = item.children().map_or(&[][..], |v| v.as_slice());
-                format!(
-                    "{acc}{indent}{prefix}{}\n",
-                    self.transform_to_mrkdwn_with_indent(children, indent_level + 1)
-                )
+        let mut records: Vec<String> = table
+            .children
+            .iter()
+            .filter_map(Node::children)
+            .skip(1)
+            .map(|cells| {
+                let fields: Vec<String> = cells
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(column, cell)| {
+                        let children = cell.children().map_or(&[][..], Vec::as_slice);
+                        let value = self.transform_to_mrkdwn(children).trim().to_string();
+                        if value.is_empty() {
+                            return None;
+                        }
+                        Some(match headers.get(column).filter(|header| !header.is_empty()) {
+                            Some(header) => format!("*{header}:* {value}"),
+                            None => value,
+                        })
+                    })
+                    .collect();
+                fields.join(", ")
             })
-            .replace("\n\n", "\n")
-            + "\n"
+            .filter(|record| !record.is_empty())
+            .collect();
+        if records.is_empty() {
+            let labels: Vec<String> = headers
+                .iter()
+                .filter(|header| !header.is_empty())
+                .map(|header| format!("*{header}*"))
+                .collect();
+            records.push(labels.join(", "));
+        }
+        format!("{}\n", records.join("\n"))
+    }
+
+    /// Returns the plain text of the header cells of a table, trimmed.
+    fn table_headers(table: &Table) -> Vec<String> {
+        table
+            .children
+            .first()
+            .and_then(Node::children)
+            .into_iter()
+            .flatten()
+            .map(|cell| Self::plain_text(cell.children().map_or(&[][..], Vec::as_slice)))
+            .map(|header| header.trim().to_string())
+            .collect()
     }
 
     /// Converts a Markdown table into a Slack [table block].
     ///
     /// Each cell is rendered as a `rich_text` cell so that inline formatting (bold, italic,
-    /// strikethrough, inline code, and links) is preserved.
+    /// strikethrough, inline code, and links) is preserved, or as a `raw_text` cell as
+    /// [`ConvertOptions::table_cells`] says. The columns are aligned as the delimiter row says
+    /// and wrap as [`ConvertOptions::column_wrap`] says.
     ///
     /// [table block]: https://docs.slack.dev/reference/block-kit/blocks/table-block/
-    fn handle_table(table: &Table) -> Block {
+    fn handle_table(&self, table: &Table) -> Block {
         let column_settings = table
             .align
             .iter()
-            .map(|align| match align {
-                AlignKind::Left => Some("left".to_string()),
-                AlignKind::Center => Some("center".to_string()),
-                AlignKind::Right => Some("right".to_string()),
-                AlignKind::None => None,
+            .enumerate()
+            .map(|(column, align)| ColumnSettings {
+                align: match align {
+                    AlignKind::Left => Some("left".to_string()),
+                    AlignKind::Center => Some("center".to_string()),
+                    AlignKind::Right => Some("right".to_string()),
+                    AlignKind::None => None,
+                },
+                is_wrapped: self.options.column_wrap().is_wrapped(column),
             })
             .collect();
 
@@ -353,17 +1701,20 @@ impl<'a> Mrkdwn<'a> {
             .children
             .iter()
             .filter_map(|row| match row {
-                Node::TableRow(row) => Some(
-                    row.children
-                        .iter()
-                        .map(|cell| match cell {
-                            Node::TableCell(cell) => Self::table_cell(&cell.children),
-                            _ => Self::table_cell(&[]),
-                        })
-                        .collect(),
-                ),
+                Node::TableRow(row) => Some(row),
                 _ => None,
             })
+            .enumerate()
+            .map(|(i, row)| {
+                let header = i == 0;
+                row.children
+                    .iter()
+                    .map(|cell| match cell {
+                        Node::TableCell(cell) => self.table_cell(&cell.children, header),
+                        _ => self.table_cell(&[], header),
+                    })
+                    .collect()
+            })
             .collect();
 
         Block::Table { column_settings, rows }
@@ -376,8 +1727,12 @@ impl<'a> Mrkdwn<'a> {
     ///
     /// [image block]: https://docs.slack.dev/reference/block-kit/blocks/image-block/
     fn handle_paragraph(&self, nodes: &[Node]) -> Vec<Block> {
-        if !nodes.iter().any(|node| matches!(node, Node::Image(_))) {
-            return vec![Block::Section(self.surround_nodes_with(nodes, "", "\n", 0))];
+        if self.options.images() != ImageStyle::Block
+            || !nodes.iter().any(|node| matches!(node, Node::Image(_)))
+        {
+            let text = self.surround_nodes_with(nodes, "", "\n", 0);
+            // A paragraph of omitted images leaves nothing, and Slack rejects empty sections.
+            return if text.trim().is_empty() { vec![] } else { vec![Block::Section(text)] };
         }
 
         let mut blocks = Vec::new();
@@ -407,22 +1762,37 @@ impl<'a> Mrkdwn<'a> {
     }
 
     /// Builds an image block from a Markdown image node.
-    ///
-    /// Slack requires a non-empty `alt_text`, so the Markdown alt text is used when present,
-    /// falling back to the image title and finally the URL.
     fn image_block(image: &Image) -> Block {
         let title = image.title.clone().filter(|title| !title.trim().is_empty());
-        let alt_text = if image.alt.trim().is_empty() {
-            title.clone().unwrap_or_else(|| image.url.clone())
-        } else {
-            image.alt.clone()
-        };
-        Block::Image { url: image.url.clone(), alt_text, title }
+        Block::Image {
+            url: image.url.clone(),
+            alt_text: Self::alt_text(image),
+            title,
+        }
+    }
+
+    /// Returns the alt text of an image block. Slack requires a non-empty `alt_text`, so the
+    /// Markdown alt text is used when present, falling back to the image title and finally the
+    /// URL.
+    fn alt_text(image: &Image) -> String {
+        if !image.alt.trim().is_empty() {
+            return image.alt.clone();
+        }
+        match &image.title {
+            Some(title) if !title.trim().is_empty() => title.clone(),
+            _ => image.url.clone(),
+        }
     }
 
-    /// Renders an image as a Slack link, used in the text/`mrkdwn` output where images cannot be
-    /// embedded. Falls back to a bare `<url>` link when the image has no alt text.
-    fn image_to_link(image: &Image) -> String {
+    /// Renders an image in the text/`mrkdwn` output, where images cannot be embedded: as a Slack
+    /// link unless [`ConvertOptions::images`] asks for the alt text or nothing. The link falls
+    /// back to a bare `<url>` when the image has no alt text.
+    fn image_to_mrkdwn(&self, image: &Image) -> String {
+        match self.options.images() {
+            ImageStyle::Block | ImageStyle::Link => {}
+            ImageStyle::AltText => return Self::escape(&image.alt),
+            ImageStyle::Omit => return String::new(),
+        }
         if image.alt.trim().is_empty() {
             format!("<{}>", Self::sanitize_url(&image.url))
         } else {
@@ -430,9 +1800,14 @@ impl<'a> Mrkdwn<'a> {
         }
     }
 
-    /// Renders a table as plain pipe-delimited text, used in the text/`mrkdwn` output and
-    /// anywhere a table is nested inside other inline content.
+    /// Renders a table as text, used in the text/`mrkdwn` output and anywhere a table is nested
+    /// inside other content, as pipe-delimited rows or, with [`TextTableStyle::Monospace`], an
+    /// aligned code block.
     fn table_to_text(&self, table: &Table, indent_level: usize) -> String {
+        if self.options.text_tables() == TextTableStyle::Monospace {
+            return Self::table_to_monospace(table);
+        }
+
         let rows: Vec<String> = table
             .children
             .iter()
@@ -459,43 +1834,178 @@ impl<'a> Mrkdwn<'a> {
         format!("{}\n", rows.join("\n"))
     }
 
-    /// Returns the display label for a footnote, preferring the original `label` (which keeps the
-    /// author's casing) and falling back to the normalized `identifier`.
-    fn footnote_label(identifier: &str, label: &Option<String>) -> String {
-        label.clone().unwrap_or_else(|| identifier.to_string())
+    /// Renders a table as a code block of plain text, padding every column to its widest cell and
+    /// aligning the cells as the table's delimiter row says (left by default). A line of dashes
+    /// separates the header row from the others.
+    fn table_to_monospace(table: &Table) -> String {
+        let rows: Vec<Vec<String>> = table
+            .children
+            .iter()
+            .filter_map(|row| match row {
+                Node::TableRow(row) => Some(
+                    row.children
+                        .iter()
+                        .map(|cell| {
+                            Self::plain_text(cell.children().map_or(&[][..], Vec::as_slice))
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| text::width(cell))
+                    .max()
+                    .unwrap_or_default()
+                    .max(1)
+            })
+            .collect();
+
+        let line = |row: &[String]| {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, &width)| {
+                    let cell = row.get(column).map_or("", String::as_str);
+                    let padding = width.saturating_sub(text::width(cell));
+                    let (left, right) = match table.align.get(column) {
+                        Some(AlignKind::Right) => (padding, 0),
+                        Some(AlignKind::Center) => (padding / 2, padding - padding / 2),
+                        _ => (0, padding),
+                    };
+                    format!("{}{cell}{}", " ".repeat(left), " ".repeat(right))
+                })
+                .collect();
+            cells.join(" | ").trim_end().to_string()
+        };
+
+        let mut lines = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            lines.push(line(row));
+            if i == 0 {
+                let dashes: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+                lines.push(dashes.join("-|-"));
+            }
+        }
+        format!("```\n{}\n```\n", Self::escape(&lines.join("\n")))
+    }
+
+    /// Builds a single table cell from inline Markdown nodes, bold in the `header` row when
+    /// [`ConvertOptions::bold_table_header`] says so.
+    fn table_cell(&self, nodes: &[Node], header: bool) -> Value {
+        let bold = header && self.options.bold_table_header();
+        match self.options.table_cells() {
+            TableCells::RawText if !bold => Self::raw_text_cell(&self.raw_text(nodes)),
+            _ => {
+                let style = if bold { Style::default().bold() } else { Style::default() };
+                Self::rich_text_cell(Self::non_empty(self.rich_text_elements(nodes, style)))
+            }
+        }
     }
 
-    /// Builds a single `rich_text` table cell from inline Markdown nodes.
-    fn table_cell(nodes: &[Node]) -> Value {
-        let mut elements = Self::rich_text_elements(nodes, Style::default());
-        // Slack rejects a `rich_text_section` with no elements, so emit an empty text element.
-        if elements.is_empty() {
-            elements.push(Self::text_element("", Style::default()));
+    /// Builds a single table cell holding unformatted text.
+    fn text_cell(&self, text: &str) -> Value {
+        match self.options.table_cells() {
+            TableCells::RawText => Self::raw_text_cell(text),
+            TableCells::RichText => {
+                Self::rich_text_cell(vec![Self::text_element(text, Style::default())])
+            }
         }
+    }
+
+    /// Builds a `raw_text` table cell.
+    fn raw_text_cell(text: &str) -> Value {
+        json!({ "type": "raw_text", "text": text })
+    }
+
+    /// Flattens inline nodes into the text of a `raw_text` cell, writing images as their alt
+    /// text, or URL when they have none.
+    fn raw_text(&self, nodes: &[Node]) -> String {
+        Self::flatten_inline(nodes, &|image| match image {
+            _ if self.options.images() == ImageStyle::Omit => String::new(),
+            image if image.alt.trim().is_empty() => image.url.clone(),
+            image => image.alt.clone(),
+        })
+    }
+
+    /// Flattens inline nodes into plain text: like [`Self::plain_text`], but keeping line
+    /// breaks and footnote labels, and writing images with `image`.
+    fn flatten_inline(nodes: &[Node], image: &dyn Fn(&Image) -> String) -> String {
+        nodes
+            .iter()
+            .map(|node| match node {
+                Node::Break(_) => "\n".to_string(),
+                Node::Html(n) if is_line_break(&n.value) => "\n".to_string(),
+                Node::Image(n) => image(n),
+                Node::FootnoteReference(n) => footnote_label(n),
+                Node::Strong(n) => Self::flatten_inline(&n.children, image),
+                Node::Emphasis(n) => Self::flatten_inline(&n.children, image),
+                Node::Delete(n) => Self::flatten_inline(&n.children, image),
+                Node::Link(n) => Self::flatten_inline(&n.children, image),
+                node => Self::plain_text(std::slice::from_ref(node)),
+            })
+            .collect()
+    }
+
+    /// Wraps `rich_text` section elements into a table cell.
+    fn rich_text_cell(elements: Vec<Value>) -> Value {
         json!({
         "type": "rich_text",
-        "elements": [ { "type": "rich_text_section", "elements": elements } ],
+        "elements": [ Self::rich_text_section(elements) ],
         })
     }
 
+    /// Wraps inline elements into a `rich_text_section`.
+    fn rich_text_section(elements: Vec<Value>) -> Value {
+        json!({ "type": "rich_text_section", "elements": elements })
+    }
+
     /// Recursively converts inline Markdown nodes into Slack `rich_text` section elements,
     /// carrying the active text style through nested formatting nodes.
-    fn rich_text_elements(nodes: &[Node], style: Style) -> Vec<Value> {
+    fn rich_text_elements(&self, nodes: &[Node], style: Style) -> Vec<Value> {
         let mut elements = Vec::new();
         for node in nodes {
             match node {
-                Node::Text(n) => elements.push(Self::text_element(&n.value, style)),
+                Node::Text(n) => elements.extend(self.text_elements(&n.value, style)),
                 Node::Strong(n) => {
-                    elements.extend(Self::rich_text_elements(&n.children, style.bold()));
+                    elements.extend(self.rich_text_elements(&n.children, style.bold()));
                 }
                 Node::Emphasis(n) => {
-                    elements.extend(Self::rich_text_elements(&n.children, style.italic()));
+                    elements.extend(self.rich_text_elements(&n.children, style.italic()));
                 }
                 Node::Delete(n) => {
-                    elements.extend(Self::rich_text_elements(&n.children, style.strike()));
+                    elements.extend(self.rich_text_elements(&n.children, style.strike()));
                 }
                 Node::InlineCode(n) => elements.push(Self::text_element(&n.value, style.code())),
                 Node::Break(_) => elements.push(Self::text_element("\n", style)),
+                Node::Html(n) if is_line_break(&n.value) => {
+                    elements.push(Self::text_element("\n", style));
+                }
+                Node::Html(n) => {
+                    elements.extend(Date::from_html(&n.value).map(|date| date.to_element()))
+                }
+                // Footnotes are only left in place for markdown blocks, which show them as
+                // written.
+                Node::FootnoteReference(n) => {
+                    elements.push(Self::text_element(&footnote_label(n), style));
+                }
+                Node::Image(n) if self.options.images() == ImageStyle::AltText => {
+                    elements.push(Self::text_element(&n.alt, style));
+                }
+                Node::Image(n)
+                    if self.options.images() != ImageStyle::Omit && !n.url.is_empty() =>
+                {
+                    let alt = if n.alt.trim().is_empty() { &n.url } else { &n.alt };
+                    let mut element = json!({ "type": "link", "url": n.url, "text": alt });
+                    if let Some(value) = style.to_value() {
+                        element["style"] = value;
+                    }
+                    elements.push(element);
+                }
                 Node::Link(n) => {
                     let mut element = json!({
                         "type": "link",
@@ -534,10 +2044,94 @@ impl<'a> Mrkdwn<'a> {
                 Node::Emphasis(n) => Self::plain_text(&n.children),
                 Node::Delete(n) => Self::plain_text(&n.children),
                 Node::Link(n) => Self::plain_text(&n.children),
+                Node::Html(n) => Date::from_html(&n.value)
+                    .map(|date| date.fallback())
+                    .unwrap_or_default(),
                 _ => String::new(),
             })
             .collect()
     }
+
+    /// Renders block-level nodes as lines of the text fallback, with lists marked as lists
+    /// nested `level` deep.
+    fn fallback_lines(&self, nodes: &[Node], level: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for node in nodes {
+            match node {
+                Node::Paragraph(n) => lines.extend(self.fallback_paragraph(&n.children)),
+                Node::Heading(n) => lines.extend(self.fallback_paragraph(&n.children)),
+                Node::Code(n) => lines.extend(n.value.lines().map(str::to_string)),
+                Node::Table(n) => lines.push(Self::describe_table(n)),
+                Node::List(n) => {
+                    let indent = " ".repeat(self.options.indent_width());
+                    let mut number = n.start.unwrap_or(1);
+                    for item in &n.children {
+                        let Node::ListItem(item) = item else { continue };
+                        let marker = match (item.checked, n.ordered) {
+                            (Some(checked), _) => self.options.checkbox(checked).to_string(),
+                            (None, true) => {
+                                format!("{}.", self.options.numbers().format(number, level))
+                            }
+                            (None, false) => self.options.bullet(level).to_string(),
+                        };
+                        number += 1;
+                        let mut item_lines =
+                            self.fallback_lines(&item.children, level + 1).into_iter();
+                        lines.push(format!("{marker} {}", item_lines.next().unwrap_or_default()));
+                        lines.extend(item_lines.map(|line| format!("{indent}{line}")));
+                    }
+                }
+                Node::Blockquote(n) => lines.extend(self.fallback_lines(&n.children, level)),
+                _ => {}
+            }
+        }
+        lines
+    }
+
+    /// Renders the inline content of a paragraph as lines of the text fallback.
+    fn fallback_paragraph(&self, nodes: &[Node]) -> Vec<String> {
+        let text = Self::flatten_inline(nodes, &Self::describe_image);
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Describes a table by its header cells and number of rows, e.g.
+    /// `[Table: Name, Score; 2 rows]`.
+    fn describe_table(table: &Table) -> String {
+        let mut headers = Self::table_headers(table);
+        headers.retain(|header| !header.is_empty());
+        let rows = match table.children.len().saturating_sub(1) {
+            1 => "1 row".to_string(),
+            count => format!("{count} rows"),
+        };
+        if headers.is_empty() {
+            format!("[Table: {rows}]")
+        } else {
+            format!("[Table: {}; {rows}]", headers.join(", "))
+        }
+    }
+
+    /// Describes an image by its alt text, or its title or URL when it has none, e.g.
+    /// `[Image: A cat]`.
+    fn describe_image(image: &Image) -> String {
+        format!("[Image: {}]", Self::alt_text(image))
+    }
+}
+
+/// Returns whether an HTML tag is `<br>`, which GFM tables use for line breaks in cells.
+pub(crate) fn is_line_break(html: &str) -> bool {
+    let html = html.trim().to_ascii_lowercase();
+    html.strip_prefix("<br")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .is_some_and(|rest| rest.trim_end_matches('/').trim().is_empty())
+}
+
+/// Returns a footnote reference as written, e.g. `[^note]`.
+fn footnote_label(reference: &FootnoteReference) -> String {
+    format!("[^{}]", reference.label.as_deref().unwrap_or(&reference.identifier))
 }
 
 /// Active inline text style while building Slack `rich_text` elements.
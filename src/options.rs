@@ -0,0 +1,459 @@
+/// How a Markdown heading is converted into blocks.
+///
+/// The mrkdwn text output has no headers, so headings are always bold there.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HeadingStyle {
+    /// A `header` block followed by a `divider` block.
+    HeaderWithDivider,
+    /// A `header` block.
+    Header,
+    /// Bold text in a `section` block (or a `rich_text` block in [`crate::BlockMode::RichText`]).
+    Bold,
+}
+
+/// How a thematic break (`---`) is rendered.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub enum DividerStyle {
+    /// A `divider` block, drawn as a `----------` line in mrkdwn text. This is the default.
+    #[default]
+    Block,
+    /// A line of mrkdwn text, in a `section` block when converting to blocks. `&`, `<`, and `>`
+    /// are escaped as Slack requires, so they cannot form entities, links, or mentions.
+    Text(String),
+    /// Nothing; thematic breaks are dropped.
+    Omit,
+}
+
+/// How an image is rendered.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ImageStyle {
+    /// An `image` block when the image is a paragraph's content, and a link to the image where
+    /// blocks cannot be used (mrkdwn text, lists, tables). This is the default.
+    #[default]
+    Block,
+    /// A link to the image, labeled with its alt text.
+    Link,
+    /// The alt text alone.
+    AltText,
+    /// Nothing; images are dropped.
+    Omit,
+}
+
+/// How the items of ordered lists are numbered in mrkdwn text.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum NumberStyle {
+    /// `1.`, `2.`, `3.` at every nesting level. This is the default.
+    #[default]
+    Decimal,
+    /// `1.` at the top level, `a.` one level deeper, then `i.`, repeating further down, like
+    /// Slack's rich text lists.
+    Nested,
+}
+
+impl NumberStyle {
+    /// Formats the number of an ordered list item at nesting `level` (0 for the outermost list).
+    pub(crate) fn format(self, number: u32, level: usize) -> String {
+        match (self, level % 3) {
+            (NumberStyle::Decimal, _) | (NumberStyle::Nested, 0) => number.to_string(),
+            (NumberStyle::Nested, 1) => letters(number),
+            (NumberStyle::Nested, _) => roman(number),
+        }
+    }
+}
+
+/// Formats `number` as lowercase letters: `a` to `z`, then `aa`, `ab`, and so on.
+fn letters(number: u32) -> String {
+    let mut letters = Vec::new();
+    let mut number = number;
+    while number > 0 {
+        number -= 1;
+        letters.push(char::from(b'a' + (number % 26) as u8));
+        number /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Formats `number` as lowercase Roman numerals, falling back to digits for 0 and above 3999.
+fn roman(number: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    if !(1..4000).contains(&number) {
+        return number.to_string();
+    }
+    let mut result = String::new();
+    let mut number = number;
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            result.push_str(numeral);
+            number -= value;
+        }
+    }
+    result
+}
+
+/// How tables are rendered in mrkdwn text, where table blocks are not available: in
+/// [`crate::Mrkdwn::mrkdwnify_raw`], and inside lists and quotes when converting to blocks.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TextTableStyle {
+    /// `| a | b |` rows with the cells' inline formatting. This is the default.
+    #[default]
+    Pipes,
+    /// A code block with every column padded to its widest cell, counting East Asian wide
+    /// characters as two columns, and aligned as the Markdown table says. Inline formatting is
+    /// dropped, since mrkdwn does not render inside code.
+    Monospace,
+}
+
+/// What a table too large for a `table` block becomes when converting to blocks, that is one
+/// with more rows or columns than [`crate::Limits`] allows.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TableFallback {
+    /// A `table` block, left to the [`crate::OverflowPolicy`] of the table limits, which fails
+    /// the conversion by default. This is the default.
+    #[default]
+    Off,
+    /// [`TableFallback::SplitRows`] when only the rows are too many and the table limit's
+    /// policy is [`crate::OverflowPolicy::Split`], so the tables can be spread over messages;
+    /// otherwise [`TableFallback::Code`] when the code block fits in a `section` block, and
+    /// [`TableFallback::Records`] when it does not.
+    Auto,
+    /// Several `table` blocks of consecutive rows, each starting with the header row. Since a
+    /// message holds a single table, these need [`crate::Mrkdwn::blockify_messages`] or a
+    /// higher table limit. Columns beyond the limit are still left to its policy.
+    SplitRows,
+    /// A `section` block with the table drawn as an aligned code block, as
+    /// [`TextTableStyle::Monospace`] draws it.
+    Code,
+    /// A `section` block with a line per row, labeling each cell with its column header, e.g.
+    /// `*Name:* Alice, *Score:* 10`.
+    Records,
+}
+
+/// What the cells of table blocks are built as.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TableCells {
+    /// `rich_text` cells, keeping inline formatting, links, mentions, emoji, and dates. This is
+    /// the default.
+    #[default]
+    RichText,
+    /// `raw_text` cells of unformatted text, for plain tables and a smaller payload.
+    RawText,
+}
+
+/// Which columns of table blocks wrap long text onto more lines instead of cutting it off.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub enum ColumnWrap {
+    /// No column wraps. This is the default.
+    #[default]
+    Off,
+    /// Every column wraps.
+    All,
+    /// The columns at these indices, counting from 0, wrap.
+    Columns(Vec<usize>),
+}
+
+impl ColumnWrap {
+    /// Returns whether the column at index `column` wraps.
+    pub(crate) fn is_wrapped(&self, column: usize) -> bool {
+        match self {
+            ColumnWrap::Off => false,
+            ColumnWrap::All => true,
+            ColumnWrap::Columns(columns) => columns.contains(&column),
+        }
+    }
+}
+
+/// How emoji in text are written. Either way, GitHub shortcodes that Slack names differently,
+/// such as `:shipit:`, are translated to Slack's names.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum EmojiStyle {
+    /// Shortcodes and Unicode emoji are kept as written. This is the default.
+    #[default]
+    Keep,
+    /// Unicode emoji become shortcodes, e.g. `🎉` becomes `:tada:`.
+    Shortcode,
+    /// Shortcodes become Unicode emoji where one exists, e.g. `:tada:` becomes `🎉`.
+    Unicode,
+}
+
+/// How footnote references are numbered.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum FootnoteStyle {
+    /// Superscript digits, e.g. `¹`. This is the default.
+    #[default]
+    Superscript,
+    /// Digits in brackets, e.g. `[1]`.
+    Bracket,
+}
+
+/// Rendering choices for [`crate::Mrkdwn`], built with [`ConvertOptions::builder`].
+///
+/// The default reproduces the crate's standard output: level 1 headings become a header and a
+/// divider, level 2 headings a header, and deeper ones bold text; lists use `•`, `☐`, and `☑`
+/// and are indented by four spaces per level, with ordered lists numbered `1.` at every level;
+/// tables in text are drawn with pipes, table blocks have `rich_text` cells in columns that do
+/// not wrap under a plain header row, and tables too large for a table block fail the
+/// conversion; thematic breaks and images become blocks; emoji are kept as written; footnotes
+/// are numbered with superscript digits; the text fallback of a message is cut to 4000
+/// characters.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConvertOptions {
+    headings: [HeadingStyle; 6],
+    bullets: Vec<String>,
+    unchecked: String,
+    checked: String,
+    indent_width: usize,
+    numbers: NumberStyle,
+    text_tables: TextTableStyle,
+    table_fallback: TableFallback,
+    table_cells: TableCells,
+    column_wrap: ColumnWrap,
+    bold_table_header: bool,
+    divider: DividerStyle,
+    images: ImageStyle,
+    emoji: EmojiStyle,
+    footnotes: FootnoteStyle,
+    fallback_chars: usize,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            headings: [
+                HeadingStyle::HeaderWithDivider,
+                HeadingStyle::Header,
+                HeadingStyle::Bold,
+                HeadingStyle::Bold,
+                HeadingStyle::Bold,
+                HeadingStyle::Bold,
+            ],
+            bullets: vec!["•".to_string()],
+            unchecked: "\u{2610}".to_string(),
+            checked: "\u{2611}".to_string(),
+            indent_width: 4,
+            numbers: NumberStyle::default(),
+            text_tables: TextTableStyle::default(),
+            table_fallback: TableFallback::default(),
+            table_cells: TableCells::default(),
+            column_wrap: ColumnWrap::default(),
+            bold_table_header: false,
+            divider: DividerStyle::default(),
+            images: ImageStyle::default(),
+            emoji: EmojiStyle::default(),
+            footnotes: FootnoteStyle::default(),
+            // Slack recommends keeping the `text` of a message under 4000 characters.
+            fallback_chars: 4000,
+        }
+    }
+}
+
+impl ConvertOptions {
+    /// Returns a builder starting from the default options.
+    pub fn builder() -> ConvertOptionsBuilder {
+        ConvertOptionsBuilder::default()
+    }
+
+    /// Returns the style of headings of `depth` (1 to 6).
+    pub fn heading(&self, depth: u8) -> HeadingStyle {
+        self.headings[usize::from(depth.clamp(1, 6)) - 1]
+    }
+
+    /// Returns the bullet glyph of a list item at nesting `level` (0 for the outermost list).
+    pub fn bullet(&self, level: usize) -> &str {
+        &self.bullets[level % self.bullets.len()]
+    }
+
+    /// Returns the glyph of a task list item.
+    pub fn checkbox(&self, checked: bool) -> &str {
+        if checked { &self.checked } else { &self.unchecked }
+    }
+
+    /// Returns the spaces a nested list is indented by, per level.
+    pub fn indent_width(&self) -> usize {
+        self.indent_width
+    }
+
+    /// Returns how ordered list items are numbered.
+    pub fn numbers(&self) -> NumberStyle {
+        self.numbers
+    }
+
+    /// Returns how tables are rendered in mrkdwn text.
+    pub fn text_tables(&self) -> TextTableStyle {
+        self.text_tables
+    }
+
+    /// Returns what tables too large for a `table` block become.
+    pub fn table_fallback(&self) -> TableFallback {
+        self.table_fallback
+    }
+
+    /// Returns what the cells of table blocks are built as.
+    pub fn table_cells(&self) -> TableCells {
+        self.table_cells
+    }
+
+    /// Returns which columns of table blocks wrap long text.
+    pub fn column_wrap(&self) -> &ColumnWrap {
+        &self.column_wrap
+    }
+
+    /// Returns whether the header row of table blocks is bold.
+    pub fn bold_table_header(&self) -> bool {
+        self.bold_table_header
+    }
+
+    /// Returns how thematic breaks are rendered.
+    pub fn divider(&self) -> &DividerStyle {
+        &self.divider
+    }
+
+    /// Returns how images are rendered.
+    pub fn images(&self) -> ImageStyle {
+        self.images
+    }
+
+    /// Returns how emoji are written.
+    pub fn emoji(&self) -> EmojiStyle {
+        self.emoji
+    }
+
+    /// Returns how footnote references are numbered.
+    pub fn footnotes(&self) -> FootnoteStyle {
+        self.footnotes
+    }
+
+    /// Returns the characters the text fallback of a message is cut to.
+    pub fn fallback_chars(&self) -> usize {
+        self.fallback_chars
+    }
+}
+
+/// Builds [`ConvertOptions`], starting from the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptionsBuilder {
+    options: ConvertOptions,
+}
+
+impl ConvertOptionsBuilder {
+    /// Sets the style of headings of `depth` (1 to 6). Other depths are ignored.
+    pub fn heading(mut self, depth: u8, style: HeadingStyle) -> Self {
+        if (1..=6).contains(&depth) {
+            self.options.headings[usize::from(depth) - 1] = style;
+        }
+        self
+    }
+
+    /// Sets the bullet glyphs, one per nesting level, repeating when lists nest deeper. An empty
+    /// list keeps the current glyphs. `&`, `<`, and `>` are escaped in mrkdwn output.
+    pub fn bullets<I, S>(mut self, bullets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let bullets: Vec<String> = bullets.into_iter().map(Into::into).collect();
+        if !bullets.is_empty() {
+            self.options.bullets = bullets;
+        }
+        self
+    }
+
+    /// Sets the glyphs of unchecked and checked task list items. `&`, `<`, and `>` are escaped in
+    /// mrkdwn output.
+    pub fn checkboxes(mut self, unchecked: impl Into<String>, checked: impl Into<String>) -> Self {
+        self.options.unchecked = unchecked.into();
+        self.options.checked = checked.into();
+        self
+    }
+
+    /// Sets the spaces a nested list is indented by, per level.
+    pub fn indent_width(mut self, width: usize) -> Self {
+        self.options.indent_width = width;
+        self
+    }
+
+    /// Sets how ordered list items are numbered.
+    pub fn numbers(mut self, numbers: NumberStyle) -> Self {
+        self.options.numbers = numbers;
+        self
+    }
+
+    /// Sets how tables are rendered in mrkdwn text.
+    pub fn text_tables(mut self, text_tables: TextTableStyle) -> Self {
+        self.options.text_tables = text_tables;
+        self
+    }
+
+    /// Sets what tables too large for a `table` block become.
+    pub fn table_fallback(mut self, table_fallback: TableFallback) -> Self {
+        self.options.table_fallback = table_fallback;
+        self
+    }
+
+    /// Sets what the cells of table blocks are built as.
+    pub fn table_cells(mut self, table_cells: TableCells) -> Self {
+        self.options.table_cells = table_cells;
+        self
+    }
+
+    /// Sets which columns of table blocks wrap long text.
+    pub fn column_wrap(mut self, column_wrap: ColumnWrap) -> Self {
+        self.options.column_wrap = column_wrap;
+        self
+    }
+
+    /// Sets whether the header row of table blocks is bold. A bold header row is built of
+    /// `rich_text` cells even with [`TableCells::RawText`], since `raw_text` has no style.
+    pub fn bold_table_header(mut self, bold: bool) -> Self {
+        self.options.bold_table_header = bold;
+        self
+    }
+
+    /// Sets how thematic breaks are rendered.
+    pub fn divider(mut self, divider: DividerStyle) -> Self {
+        self.options.divider = divider;
+        self
+    }
+
+    /// Sets how images are rendered.
+    pub fn images(mut self, images: ImageStyle) -> Self {
+        self.options.images = images;
+        self
+    }
+
+    /// Sets how emoji are written.
+    pub fn emoji(mut self, emoji: EmojiStyle) -> Self {
+        self.options.emoji = emoji;
+        self
+    }
+
+    /// Sets how footnote references are numbered.
+    pub fn footnotes(mut self, footnotes: FootnoteStyle) -> Self {
+        self.options.footnotes = footnotes;
+        self
+    }
+
+    /// Sets the characters the text fallback of a message is cut to, marking the cut with `…`.
+    pub fn fallback_chars(mut self, max: usize) -> Self {
+        self.options.fallback_chars = max;
+        self
+    }
+
+    /// Returns the built options.
+    pub fn build(self) -> ConvertOptions {
+        self.options
+    }
+}
@@ -0,0 +1,72 @@
+//! Reference-style links and images, such as `[docs][1]`, `[docs][]`, or `[docs]` with a
+//! `[1]: https://…` definition elsewhere in the document.
+//!
+//! References are resolved into inline links and images right after parsing, so every output
+//! renders them exactly like `[docs](https://…)`. The definitions themselves render as nothing.
+
+use std::collections::HashMap;
+
+use markdown::mdast::{Image, Link, Node};
+
+/// Replaces every link and image reference under `nodes` with the link or image its definition
+/// describes. Identifiers are already normalized by the parser, and the first definition of an
+/// identifier wins.
+pub(crate) fn resolve(nodes: &mut [Node]) {
+    let mut definitions = HashMap::new();
+    collect(nodes, &mut definitions);
+    if !definitions.is_empty() {
+        replace(nodes, &definitions);
+    }
+}
+
+/// Collects the `(url, title)` of every definition under `nodes`, by identifier.
+fn collect(nodes: &[Node], definitions: &mut HashMap<String, (String, Option<String>)>) {
+    for node in nodes {
+        match node {
+            Node::Definition(definition) => {
+                definitions
+                    .entry(definition.identifier.clone())
+                    .or_insert_with(|| (definition.url.clone(), definition.title.clone()));
+            }
+            _ => {
+                if let Some(children) = node.children() {
+                    collect(children, definitions);
+                }
+            }
+        }
+    }
+}
+
+fn replace(nodes: &mut [Node], definitions: &HashMap<String, (String, Option<String>)>) {
+    for node in nodes.iter_mut() {
+        let resolved = match node {
+            Node::LinkReference(reference) => {
+                definitions.get(&reference.identifier).map(|(url, title)| {
+                    Node::Link(Link {
+                        children: std::mem::take(&mut reference.children),
+                        position: reference.position.take(),
+                        url: url.clone(),
+                        title: title.clone(),
+                    })
+                })
+            }
+            Node::ImageReference(reference) => {
+                definitions.get(&reference.identifier).map(|(url, title)| {
+                    Node::Image(Image {
+                        position: reference.position.take(),
+                        alt: std::mem::take(&mut reference.alt),
+                        url: url.clone(),
+                        title: title.clone(),
+                    })
+                })
+            }
+            _ => None,
+        };
+        if let Some(resolved) = resolved {
+            *node = resolved;
+        }
+        if let Some(children) = node.children_mut() {
+            replace(children, definitions);
+        }
+    }
+}
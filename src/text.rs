@@ -0,0 +1,242 @@
+//! Helpers for cutting rendered `mrkdwn` text into pieces that fit Slack's character limits,
+//! and for measuring text laid out in a monospace font.
+//!
+//! The text handled here is already escaped and formatted, so cuts must never land inside an
+//! HTML entity produced by escaping (`&amp;`) or inside a `<url|text>` link, which would make
+//! Slack render garbage. Such spans are treated as indivisible atoms.
+
+/// Characters reserved in every piece for the code fence that may have to be closed at its end
+/// (`\n```\n`) and reopened at the start of the next piece (```` ```\n ````).
+const FENCE_OVERHEAD: usize = 9;
+
+/// Marker appended where content was cut off.
+pub(crate) const ELLIPSIS: &str = "…";
+
+/// Boundaries a text can be cut at, from the most to the least preferred.
+#[derive(Clone, Copy)]
+enum Level {
+    Paragraph,
+    Line,
+    Word,
+    Atom,
+}
+
+impl Level {
+    fn next(self) -> Self {
+        match self {
+            Level::Paragraph => Level::Line,
+            Level::Line => Level::Word,
+            Level::Word | Level::Atom => Level::Atom,
+        }
+    }
+}
+
+/// Splits `text` into pieces of at most `max` characters each.
+///
+/// Cuts prefer paragraph boundaries, then line boundaries (so list items stay whole), then word
+/// boundaries. When a cut falls inside a fenced code block, the piece before it gets a closing
+/// fence and the piece after it an opening one, so both render as code.
+pub(crate) fn split(text: &str, max: usize) -> Vec<String> {
+    if text.chars().count() <= max {
+        return vec![text.to_string()];
+    }
+
+    let budget = if text.contains("```") { max.saturating_sub(FENCE_OVERHEAD) } else { max };
+    let pieces = pack(text, budget.max(1), Level::Paragraph);
+    close_fences(pieces)
+}
+
+/// Cuts `text` to at most `max` characters, marking the cut with [`ELLIPSIS`].
+///
+/// A fenced code block left open by the cut is closed after the marker.
+pub(crate) fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+
+    let fenced = text.contains("```");
+    let budget = if fenced { max.saturating_sub(FENCE_OVERHEAD) } else { max };
+    let budget = budget.saturating_sub(ELLIPSIS.chars().count());
+
+    let mut result = String::new();
+    let mut len = 0;
+    for (_, atom) in atoms(text) {
+        len += atom.chars().count();
+        if len > budget {
+            break;
+        }
+        result.push_str(atom);
+    }
+    result.push_str(ELLIPSIS);
+
+    if fenced && has_open_fence(&result) {
+        result.push_str("\n```\n");
+    }
+    result
+}
+
+/// Returns the columns `text` takes in a monospace font: East Asian wide and fullwidth
+/// characters and emoji take two, combining marks and other zero-width characters none.
+pub(crate) fn width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    match u32::from(c) {
+        0x00..=0x1F
+        | 0x7F..=0x9F
+        | 0x0300..=0x036F
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0x1F3FB..=0x1F3FF
+        | 0xE0000..=0xE01EF => 0,
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x2329..=0x232A
+        | 0x23E9..=0x23EC
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xA960..=0xA97F
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F004
+        | 0x1F0CF
+        | 0x1F18E
+        | 0x1F191..=0x1F19A
+        | 0x1F200..=0x1F251
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F7E0..=0x1F7EB
+        | 0x1F900..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Greedily packs the units of `text` at `level` into pieces of at most `max` characters,
+/// falling back to a finer level for any unit that is too long on its own.
+fn pack(text: &str, max: usize, level: Level) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for unit in units(text, level) {
+        let len = unit.chars().count();
+        if len > max && !matches!(level, Level::Atom) {
+            if !current.is_empty() {
+                pieces.push(std::mem::take(&mut current));
+            }
+            let mut sub = pack(unit, max, level.next());
+            current = sub.pop().unwrap_or_default();
+            current_len = current.chars().count();
+            pieces.extend(sub);
+            continue;
+        }
+
+        if current_len + len > max && !current.is_empty() {
+            pieces.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        current.push_str(unit);
+        current_len += len;
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// Cuts `text` into consecutive units at `level`, each keeping its trailing separator so the
+/// units concatenate back to `text`.
+fn units(text: &str, level: Level) -> Vec<&str> {
+    match level {
+        Level::Paragraph => text.split_inclusive("\n\n").collect(),
+        Level::Line => text.split_inclusive('\n').collect(),
+        Level::Word => {
+            let mut units = Vec::new();
+            let mut start = 0;
+            for (offset, atom) in atoms(text) {
+                if atom == " " {
+                    units.push(&text[start..offset + 1]);
+                    start = offset + 1;
+                }
+            }
+            if start < text.len() {
+                units.push(&text[start..]);
+            }
+            units
+        }
+        Level::Atom => atoms(text).into_iter().map(|(_, atom)| atom).collect(),
+    }
+}
+
+/// Cuts `text` into indivisible atoms with their byte offsets: a whole `<...>` link or mention,
+/// a whole `&...;` entity, or otherwise a single character.
+fn atoms(text: &str) -> Vec<(usize, &str)> {
+    let mut atoms = Vec::new();
+    let mut offset = 0;
+    while let Some(c) = text[offset..].chars().next() {
+        let rest = &text[offset..];
+        let len = match c {
+            '<' => rest
+                .find(['>', '\n'])
+                .filter(|&end| rest.as_bytes()[end] == b'>')
+                .map(|end| end + 1),
+            '&' => rest
+                .char_indices()
+                .skip(1)
+                .take(9)
+                .find(|&(_, c)| !(c.is_ascii_alphanumeric() || c == '#'))
+                .filter(|&(_, c)| c == ';')
+                .map(|(end, _)| end + 1),
+            _ => None,
+        }
+        .unwrap_or(c.len_utf8());
+        atoms.push((offset, &text[offset..offset + len]));
+        offset += len;
+    }
+    atoms
+}
+
+/// Closes a code fence left open at the end of a piece and reopens it at the start of the next.
+fn close_fences(pieces: Vec<String>) -> Vec<String> {
+    let mut open = false;
+    pieces
+        .into_iter()
+        .map(|piece| {
+            let mut piece = if open { format!("```\n{piece}") } else { piece };
+            let fences = piece
+                .lines()
+                .filter(|line| line.trim_start().starts_with("```"))
+                .count();
+            open = fences % 2 == 1;
+            if open {
+                if !piece.ends_with('\n') {
+                    piece.push('\n');
+                }
+                piece.push_str("```\n");
+            }
+            piece
+        })
+        .collect()
+}
+
+/// Returns whether `text` ends inside a fenced code block.
+fn has_open_fence(text: &str) -> bool {
+    text.lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count()
+        % 2
+        == 1
+}
@@ -0,0 +1,165 @@
+//! View payloads for the surfaces apps open or publish: modals and the Home tab.
+
+use serde_json::{Map, Value, json};
+
+use crate::{Block, Error, Result, Surface};
+
+/// The most characters of a modal's `title`, `close`, and `submit` text.
+const MAX_BUTTON_CHARS: usize = 24;
+
+/// A [view](https://docs.slack.dev/reference/views/) payload for `views.open`, `views.push`,
+/// `views.update`, or `views.publish`. Built with [`crate::Mrkdwn::modal`] or
+/// [`crate::Mrkdwn::home_tab`], or from blocks with [`View::modal`] or [`View::home_tab`].
+///
+/// Fields left unset are omitted from the payload, so Slack's defaults apply.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct View {
+    surface: Surface,
+    title: Option<String>,
+    blocks: Vec<Block>,
+    close: Option<String>,
+    submit: Option<String>,
+    callback_id: Option<String>,
+    private_metadata: Option<String>,
+    external_id: Option<String>,
+}
+
+impl View {
+    /// Creates a modal titled `title` holding `blocks`.
+    pub fn modal(title: impl Into<String>, blocks: Vec<Block>) -> Self {
+        Self {
+            title: Some(title.into()),
+            ..Self::new(Surface::Modal, blocks)
+        }
+    }
+
+    /// Creates a Home tab holding `blocks`.
+    pub fn home_tab(blocks: Vec<Block>) -> Self {
+        Self::new(Surface::HomeTab, blocks)
+    }
+
+    fn new(surface: Surface, blocks: Vec<Block>) -> Self {
+        Self {
+            surface,
+            title: None,
+            blocks,
+            close: None,
+            submit: None,
+            callback_id: None,
+            private_metadata: None,
+            external_id: None,
+        }
+    }
+
+    /// Returns the surface of the view, [`Surface::Modal`] or [`Surface::HomeTab`].
+    pub fn surface(&self) -> Surface {
+        self.surface
+    }
+
+    /// Returns the blocks of the view.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Sets the label of a modal's close button. Ignored for the Home tab.
+    pub fn close(mut self, close: impl Into<String>) -> Self {
+        self.close = Some(close.into());
+        self
+    }
+
+    /// Sets the label of a modal's submit button. Ignored for the Home tab.
+    pub fn submit(mut self, submit: impl Into<String>) -> Self {
+        self.submit = Some(submit.into());
+        self
+    }
+
+    /// Sets the identifier the app receives back in interactions with the view.
+    pub fn callback_id(mut self, callback_id: impl Into<String>) -> Self {
+        self.callback_id = Some(callback_id.into());
+        self
+    }
+
+    /// Sets the text the app receives back in interactions with the view.
+    pub fn private_metadata(mut self, private_metadata: impl Into<String>) -> Self {
+        self.private_metadata = Some(private_metadata.into());
+        self
+    }
+
+    /// Sets the identifier, unique within the workspace, that the view can be updated by.
+    pub fn external_id(mut self, external_id: impl Into<String>) -> Self {
+        self.external_id = Some(external_id.into());
+        self
+    }
+
+    /// Builds the JSON payload, e.g. `{"type":"modal","title":{…},"blocks":[…]}`.
+    ///
+    /// # Errors
+    ///
+    /// This returns [`Error::InvalidView`] when a modal's title, close, or submit text is
+    /// empty or longer than 24 characters, or when there are more than 100 blocks. A block
+    /// type the surface does not allow returns [`Error::BlockNotAllowed`].
+    pub fn to_value(&self) -> Result<Value> {
+        if self.blocks.len() > self.surface.max_blocks() {
+            return Err(Error::InvalidView {
+                message: format!(
+                    "{} blocks, Slack allows {}",
+                    self.blocks.len(),
+                    self.surface.max_blocks()
+                ),
+            });
+        }
+        if let Some(block) = self.blocks.iter().find(|block| !self.surface.allows(block)) {
+            let block_type = Value::from(block.clone())["type"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            return Err(Error::BlockNotAllowed { block_type, surface: self.surface });
+        }
+
+        let mut payload = Map::new();
+        match self.surface {
+            Surface::Modal => {
+                payload.insert("type".to_string(), json!("modal"));
+                let buttons =
+                    [("title", &self.title), ("close", &self.close), ("submit", &self.submit)];
+                for (key, text) in buttons {
+                    let Some(text) = text else { continue };
+                    let chars = text.chars().count();
+                    if chars == 0 || chars > MAX_BUTTON_CHARS {
+                        return Err(Error::InvalidView {
+                            message: format!(
+                                "{key} has {chars} characters, Slack allows 1 to {MAX_BUTTON_CHARS}"
+                            ),
+                        });
+                    }
+                    payload.insert(key.to_string(), json!({ "type": "plain_text", "text": text }));
+                }
+            }
+            _ => {
+                payload.insert("type".to_string(), json!("home"));
+            }
+        }
+        let blocks = self.blocks.iter().cloned().map(Value::from).collect();
+        payload.insert("blocks".to_string(), Value::Array(blocks));
+        let fields = [
+            ("callback_id", &self.callback_id),
+            ("private_metadata", &self.private_metadata),
+            ("external_id", &self.external_id),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                payload.insert(key.to_string(), json!(value));
+            }
+        }
+        Ok(Value::Object(payload))
+    }
+
+    /// Serializes the payload into a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Self::to_value`].
+    pub fn to_json(&self) -> Result<String> {
+        Ok(self.to_value()?.to_string())
+    }
+}